@@ -0,0 +1,86 @@
+//! `cargo saucer check` and `cargo saucer expand`: run codegen on a crate
+//! outside its build script.
+
+use std::path::Path;
+use std::process::ExitCode;
+
+use saucer_core::build::{BuildError, RuntimePlan};
+
+/// Run hygiene checks and discovery, then summarize what a build would
+/// generate.
+pub fn check(manifest_dir: &Path) -> ExitCode {
+    let plan = match RuntimePlan::resolve(manifest_dir) {
+        Ok(plan) => plan,
+        Err(errors) => return report(&errors),
+    };
+
+    println!("package {}", plan.package_name());
+    section("managers", plan.managers(), |(krate, request)| {
+        format!("{} ({})", krate, request)
+    });
+    section("templates", plan.templates(), |(krate, module, path)| {
+        let path = path.strip_prefix(manifest_dir).unwrap_or(path);
+        format!(
+            "{}::{} ({})",
+            krate.replace('-', "_"),
+            module,
+            path.display()
+        )
+    });
+    section("helpers", plan.used_helpers(), |(manager, helper)| {
+        format!("{}::command::{}", manager, helper)
+    });
+    section("ports", plan.ports(), |(name, direction)| {
+        format!("{} ({})", name, direction)
+    });
+
+    // Helper signatures are only checked while emitting.
+    match plan.runtime_source() {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(errors) => report(&errors),
+    }
+}
+
+/// Print the generated `runtime.rs`, optionally followed by each transformed
+/// template under the path it is included from.
+pub fn expand(manifest_dir: &Path, templates: bool) -> ExitCode {
+    let result =
+        RuntimePlan::resolve(manifest_dir).and_then(|plan| Ok((plan.runtime_source()?, plan)));
+    let (source, plan) = match result {
+        Ok(expanded) => expanded,
+        Err(errors) => return report(&errors),
+    };
+
+    print!("{}", source);
+    if templates {
+        for (path, code) in plan.template_sources() {
+            println!("\n// OUT_DIR/{}\n", path);
+            print!("{}", code);
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn section<T>(title: &str, items: impl Iterator<Item = T>, line: impl Fn(T) -> String) {
+    println!("{}:", title);
+    let mut empty = true;
+    for item in items {
+        println!("  {}", line(item));
+        empty = false;
+    }
+    if empty {
+        println!("  (none)");
+    }
+}
+
+fn report(errors: &[BuildError]) -> ExitCode {
+    for error in errors {
+        eprintln!("{}\n", error.render());
+    }
+    eprintln!(
+        "error: found {} problem{}",
+        errors.len(),
+        if errors.len() == 1 { "" } else { "s" }
+    );
+    ExitCode::FAILURE
+}
@@ -0,0 +1,164 @@
+//! `cargo saucer`: scaffold and inspect saucer apps and effect managers.
+//!
+//! `check` and `expand` run the same codegen as a build script through
+//! `saucer_core::build::RuntimePlan`, so they report what a build would
+//! without compiling anything.
+
+mod inspect;
+mod scaffold;
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use scaffold::NewOptions;
+
+const USAGE: &str = "\
+Scaffold and inspect saucer apps
+
+Usage: cargo saucer <command> [options]
+
+Commands:
+  new app <path>        Create an app crate with a template and build script
+  new manager <path>    Create an effect manager crate
+  check                 Run hygiene checks and discovery without building
+  expand                Print the generated runtime.rs
+
+Options:
+  --name <name>           Package name for `new` (defaults to the directory name)
+  --saucer <dir>          Local saucer checkout to depend on (required by `new`)
+  --manifest-path <path>  Cargo.toml of the crate to inspect (`check`, `expand`)
+  --templates             Also print the transformed templates (`expand`)
+  -h, --help              Print this help
+";
+
+enum Command {
+    NewApp(PathBuf),
+    NewManager(PathBuf),
+    Check,
+    Expand,
+    Help,
+}
+
+struct Args {
+    command: Command,
+    new: NewOptions,
+    manifest_path: Option<PathBuf>,
+    templates: bool,
+}
+
+impl Args {
+    fn parse(raw: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut raw = raw.into_iter();
+        let mut positional = Vec::new();
+        let mut new = NewOptions {
+            name: None,
+            saucer: None,
+        };
+        let mut manifest_path = None;
+        let mut templates = false;
+        let mut help = false;
+
+        while let Some(arg) = raw.next() {
+            let mut value = |flag: &str| {
+                raw.next()
+                    .ok_or_else(|| format!("`{}` expects a value", flag))
+            };
+            match arg.as_str() {
+                "--name" => new.name = Some(value("--name")?),
+                "--saucer" => new.saucer = Some(value("--saucer")?.into()),
+                "--manifest-path" => manifest_path = Some(value("--manifest-path")?.into()),
+                "--templates" => templates = true,
+                "-h" | "--help" => help = true,
+                flag if flag.starts_with('-') => return Err(format!("unknown option `{}`", flag)),
+                _ => positional.push(arg),
+            }
+        }
+
+        let positional: Vec<&str> = positional.iter().map(String::as_str).collect();
+        let command = match positional[..] {
+            _ if help => Command::Help,
+            [] => Command::Help,
+            ["new", "app", path] => Command::NewApp(path.into()),
+            ["new", "manager", path] => Command::NewManager(path.into()),
+            ["new", ..] => return Err("expected `new app <path>` or `new manager <path>`".into()),
+            ["check"] => Command::Check,
+            ["expand"] => Command::Expand,
+            [other, ..] => return Err(format!("unknown command `{}`", other)),
+        };
+
+        Ok(Self {
+            command,
+            new,
+            manifest_path,
+            templates,
+        })
+    }
+}
+
+fn main() -> ExitCode {
+    // Cargo runs `cargo saucer ...` as `cargo-saucer saucer ...`.
+    let mut raw: Vec<String> = std::env::args().skip(1).collect();
+    if raw.first().map(String::as_str) == Some("saucer") {
+        raw.remove(0);
+    }
+
+    let args = match Args::parse(raw) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(args) {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<ExitCode, String> {
+    match args.command {
+        Command::Help => print!("{}", USAGE),
+        Command::NewApp(dir) => {
+            let name = scaffold::new_app(&dir, &args.new)?;
+            println!("Created app `{}` in {}", name, dir.display());
+        }
+        Command::NewManager(dir) => {
+            let name = scaffold::new_manager(&dir, &args.new)?;
+            println!("Created effect manager `{}` in {}", name, dir.display());
+        }
+        Command::Check => {
+            let dir = manifest_dir(args.manifest_path.as_deref())?;
+            return Ok(inspect::check(&dir));
+        }
+        Command::Expand => {
+            let dir = manifest_dir(args.manifest_path.as_deref())?;
+            return Ok(inspect::expand(&dir, args.templates));
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The directory of `--manifest-path`, or of the nearest `Cargo.toml` above
+/// the current directory.
+fn manifest_dir(manifest_path: Option<&Path>) -> Result<PathBuf, String> {
+    if let Some(path) = manifest_path {
+        let path = path
+            .canonicalize()
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        return Ok(path
+            .parent()
+            .expect("a manifest path has a parent")
+            .to_path_buf());
+    }
+
+    let cwd =
+        std::env::current_dir().map_err(|e| format!("cannot read the current directory: {}", e))?;
+    cwd.ancestors()
+        .find(|dir| dir.join("Cargo.toml").is_file())
+        .map(Path::to_path_buf)
+        .ok_or_else(|| format!("no Cargo.toml in {} or any parent", cwd.display()))
+}
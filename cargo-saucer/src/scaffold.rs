@@ -0,0 +1,380 @@
+//! `cargo saucer new`: lay out app and effect manager crates the way codegen
+//! expects them (metadata block, `requests.rs`, breadcrumb `command.rs`).
+
+use std::path::{Path, PathBuf};
+
+pub struct NewOptions {
+    /// Package name; defaults to the directory name.
+    pub name: Option<String>,
+    /// Local saucer checkout to take path dependencies from. Required until
+    /// the crates are published.
+    pub saucer: Option<PathBuf>,
+}
+
+const APP_MANIFEST: &str = r#"[package]
+name = "{{name}}"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[package.metadata.saucer]
+has_templates = true
+
+[dependencies]
+{{saucer_core}}
+{{saucer_time_manager}}
+tokio = { version = "1", features = ["rt", "macros", "time"] }
+
+[build-dependencies]
+{{saucer_core_build}}
+"#;
+
+const APP_BUILD_RS: &str = r#"fn main() {
+    saucer_core::build::generate_runtime();
+}
+"#;
+
+const APP_MAIN_RS: &str = r#"mod runtime {
+    include!(concat!(env!("OUT_DIR"), "/runtime.rs"));
+}
+
+use runtime::sync::Runtime;
+use runtime::{{module}}::app;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    Runtime::new(
+        app::init,
+        app::update,
+        app::subscriptions,
+        app::view,
+        saucer_core::no_op_reconciler(),
+        saucer_core::no_op_observer(),
+    )
+    .run()
+    .await;
+}
+"#;
+
+const APP_TEMPLATE: &str = r#"use saucer_core::command::shutdown;
+use saucer_core::{Cmd, Sub};
+use saucer_time_manager::command::every;
+use saucer_time_manager::Posix;
+use std::time::Duration;
+
+pub struct Model {
+    pub ticks: u32,
+    pub last_tick: Option<Posix>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Msg {
+    Tick(Posix),
+}
+
+pub fn init() -> (Model, Cmd<Msg>) {
+    (
+        Model {
+            ticks: 0,
+            last_tick: None,
+        },
+        Cmd::none(),
+    )
+}
+
+pub fn update(mut model: Model, msg: Msg) -> (Model, Cmd<Msg>) {
+    match msg {
+        Msg::Tick(at) => {
+            model.ticks += 1;
+            model.last_tick = Some(at);
+            if model.ticks == 3 {
+                (model, shutdown())
+            } else {
+                (model, Cmd::none())
+            }
+        }
+    }
+}
+
+pub fn subscriptions(_model: &Model) -> Sub<Msg> {
+    every(Duration::from_secs(1), Msg::Tick)
+}
+
+pub fn view(model: &Model) {
+    if let Some(at) = model.last_tick {
+        println!("tick {} at {}ms", model.ticks, at.millis());
+    }
+}
+"#;
+
+const MANAGER_MANIFEST: &str = r#"[package]
+name = "{{name}}"
+version = "0.1.0"
+edition = "2021"
+
+[package.metadata.saucer]
+effect_manager = true
+request_type = "{{type}}Request"
+manager_type = "{{type}}Manager"
+self_msg_type = "()"
+
+[dependencies]
+{{saucer_core}}
+"#;
+
+const MANAGER_LIB_RS: &str = r#"//! {{name}}: effect manager for saucer-core.
+//!
+//! Fictional imports expect helpers under `{{module}}::command::*`.
+//! The real helpers live in `requests.rs`; `command.rs` is an empty breadcrumb.
+
+mod requests;
+
+pub use requests::{echo, {{type}}Request};
+
+use saucer_core::{EffectManager, Router};
+
+pub struct {{type}}Manager;
+
+impl EffectManager for {{type}}Manager {
+    type State<Msg> = ();
+    type Request<Msg> = {{type}}Request<Msg>;
+    type SelfMsg = ();
+
+    fn init<Msg: Send + 'static>() {}
+
+    fn on_effects<Msg: Send + 'static>(
+        &self,
+        router: &Router<Msg, ()>,
+        _state: (),
+        effects: Vec<{{type}}Request<Msg>>,
+    ) {
+        for request in effects {
+            match request {
+                {{type}}Request::Echo { value, returns } => router.send_to_app(returns(value)),
+            }
+        }
+    }
+}
+"#;
+
+const MANAGER_REQUESTS_RS: &str = r#"use std::sync::Arc;
+
+/// Requests apps send to the manager.
+#[derive(Clone)]
+pub enum {{type}}Request<Msg> {
+    Echo {
+        value: String,
+        returns: Arc<dyn Fn(String) -> Msg + Send + Sync>,
+    },
+}
+
+impl<Msg: 'static> {{type}}Request<Msg> {
+    pub fn map<Msg2>(
+        self,
+        f: impl Fn(Msg) -> Msg2 + Send + Sync + Clone + 'static,
+    ) -> {{type}}Request<Msg2> {
+        match self {
+            {{type}}Request::Echo { value, returns } => {{type}}Request::Echo {
+                value,
+                returns: Arc::new(move |v| f(returns(v))),
+            },
+        }
+    }
+}
+
+impl<Msg> std::fmt::Debug for {{type}}Request<Msg> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            {{type}}Request::Echo { value, .. } => f
+                .debug_struct("{{type}}Request::Echo")
+                .field("value", value)
+                .finish(),
+        }
+    }
+}
+
+impl<Msg> saucer_core::RequestFields for {{type}}Request<Msg> {
+    fn variant(&self) -> &'static str {
+        match self {
+            {{type}}Request::Echo { .. } => "Echo",
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        match self {
+            {{type}}Request::Echo { value, .. } => vec![("value", format!("{:?}", value))],
+        }
+    }
+}
+
+/// Send `value` straight back to the app.
+pub fn echo<Msg: 'static>(
+    value: String,
+    returns: impl Fn(String) -> Msg + Send + Sync + 'static,
+) -> {{type}}Request<Msg> {
+    {{type}}Request::Echo {
+        value,
+        returns: Arc::new(returns),
+    }
+}
+"#;
+
+const MANAGER_COMMAND_RS: &str =
+    "// commands are generated; see requests.rs for real helpers and request definitions.\n";
+
+/// Create an app crate in `dir`, returning its package name.
+pub fn new_app(dir: &Path, options: &NewOptions) -> Result<String, String> {
+    let name = package_name(dir, options)?;
+    let deps = Dependencies::new(options)?;
+    let vars = [
+        ("name", name.clone()),
+        ("module", name.replace('-', "_")),
+        ("saucer_core", deps.line("saucer-core", "")),
+        ("saucer_time_manager", deps.line("saucer-time-manager", "")),
+        (
+            "saucer_core_build",
+            deps.line("saucer-core", r#", features = ["build"]"#),
+        ),
+    ];
+    write_crate(
+        dir,
+        &[
+            ("Cargo.toml", APP_MANIFEST),
+            ("build.rs", APP_BUILD_RS),
+            ("src/main.rs", APP_MAIN_RS),
+            ("src/app.tea.rs", APP_TEMPLATE),
+        ],
+        &vars,
+    )?;
+    Ok(name)
+}
+
+/// Create an effect manager crate in `dir`, returning its package name.
+pub fn new_manager(dir: &Path, options: &NewOptions) -> Result<String, String> {
+    let name = package_name(dir, options)?;
+    let deps = Dependencies::new(options)?;
+    let vars = [
+        ("name", name.clone()),
+        ("module", name.replace('-', "_")),
+        ("type", manager_type_prefix(&name)),
+        ("saucer_core", deps.line("saucer-core", "")),
+    ];
+    write_crate(
+        dir,
+        &[
+            ("Cargo.toml", MANAGER_MANIFEST),
+            ("src/lib.rs", MANAGER_LIB_RS),
+            ("src/requests.rs", MANAGER_REQUESTS_RS),
+            ("src/command.rs", MANAGER_COMMAND_RS),
+        ],
+        &vars,
+    )?;
+    Ok(name)
+}
+
+fn package_name(dir: &Path, options: &NewOptions) -> Result<String, String> {
+    let name = match &options.name {
+        Some(name) => name.clone(),
+        None => dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| format!("cannot infer a package name from {}", dir.display()))?
+            .to_string(),
+    };
+    let valid = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        && name.starts_with(|c: char| c.is_ascii_alphabetic());
+    if !valid {
+        return Err(format!(
+            "`{}` is not a valid package name; use letters, digits, `-` and `_`, starting with a letter",
+            name
+        ));
+    }
+    Ok(name)
+}
+
+/// `saucer-chat-manager` -> `Chat`: the request and manager types are named
+/// after what the crate manages.
+fn manager_type_prefix(name: &str) -> String {
+    let trimmed = name.strip_prefix("saucer-").unwrap_or(name);
+    let trimmed = trimmed
+        .strip_suffix("-manager")
+        .or_else(|| trimmed.strip_suffix("_manager"))
+        .filter(|t| !t.is_empty())
+        .unwrap_or(trimmed);
+    trimmed
+        .split(['-', '_'])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            let first = chars.next().expect("split parts are non-empty");
+            first.to_ascii_uppercase().to_string() + chars.as_str()
+        })
+        .collect()
+}
+
+/// Where scaffolded crates get saucer from. Until the crates are published,
+/// that is always a local checkout.
+struct Dependencies {
+    checkout: PathBuf,
+}
+
+impl Dependencies {
+    fn new(options: &NewOptions) -> Result<Self, String> {
+        let Some(dir) = &options.saucer else {
+            return Err(
+                "saucer is not published to crates.io yet; pass `--saucer <dir>` with a local checkout"
+                    .to_string(),
+            );
+        };
+        let dir = dir
+            .canonicalize()
+            .map_err(|e| format!("cannot use {} as a saucer checkout: {}", dir.display(), e))?;
+        if !dir.join("saucer-core").join("Cargo.toml").is_file() {
+            return Err(format!(
+                "{} is not a saucer checkout (no saucer-core/Cargo.toml)",
+                dir.display()
+            ));
+        }
+        Ok(Self { checkout: dir })
+    }
+
+    fn line(&self, krate: &str, extra: &str) -> String {
+        format!(
+            "{} = {{ path = {}{} }}",
+            krate,
+            toml_string(&self.checkout.join(krate).display().to_string()),
+            extra
+        )
+    }
+}
+
+fn toml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn write_crate(dir: &Path, files: &[(&str, &str)], vars: &[(&str, String)]) -> Result<(), String> {
+    if dir.exists()
+        && std::fs::read_dir(dir)
+            .map_err(|e| format!("cannot read {}: {}", dir.display(), e))?
+            .next()
+            .is_some()
+    {
+        return Err(format!("{} already exists and is not empty", dir.display()));
+    }
+    for (relative, template) in files {
+        let path = dir.join(relative);
+        std::fs::create_dir_all(path.parent().expect("scaffolded files have a parent"))
+            .map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+        std::fs::write(&path, render(template, vars))
+            .map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+fn render(template: &str, vars: &[(&str, String)]) -> String {
+    vars.iter().fold(template.to_string(), |out, (key, value)| {
+        out.replace(&format!("{{{{{}}}}}", key), value)
+    })
+}
@@ -0,0 +1,287 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn cargo_saucer(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cargo-saucer"))
+        .arg("saucer")
+        .args(args)
+        .output()
+        .expect("run cargo-saucer")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+/// A fresh directory to scaffold into, outside any workspace.
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("cargo-saucer-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("create scratch dir");
+    dir
+}
+
+fn checkout() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("cargo-saucer lives in the saucer checkout")
+        .to_path_buf()
+}
+
+fn fixture(name: &str) -> String {
+    checkout()
+        .join("saucer-core/tests")
+        .join(name)
+        .join("Cargo.toml")
+        .display()
+        .to_string()
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args([
+            "-c",
+            "user.name=saucer",
+            "-c",
+            "user.email=saucer@example.com",
+        ])
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .expect("run git");
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+fn read(path: PathBuf) -> String {
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("read {}: {}", path.display(), e))
+}
+
+#[test]
+fn new_app_scaffolds_a_crate_codegen_accepts() {
+    let dir = scratch("new-app");
+    let app = dir.join("tick-app");
+    let output = cargo_saucer(&[
+        "new",
+        "app",
+        app.to_str().unwrap(),
+        "--saucer",
+        checkout().to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let manifest = read(app.join("Cargo.toml"));
+    assert!(!manifest.contains("exclude"), "{}", manifest);
+    assert!(manifest.contains("has_templates = true"), "{}", manifest);
+    assert!(read(app.join("src/main.rs")).contains("use runtime::tick_app::app;"));
+    assert!(app.join("build.rs").is_file());
+
+    let manifest_path = app.join("Cargo.toml");
+    let output = cargo_saucer(&["check", "--manifest-path", manifest_path.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("tick_app::app (src/app.tea.rs)"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn new_manager_names_types_after_the_crate() {
+    let dir = scratch("new-manager");
+    let manager = dir.join("saucer-chat-manager");
+    let output = cargo_saucer(&[
+        "new",
+        "manager",
+        manager.to_str().unwrap(),
+        "--saucer",
+        checkout().to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let manifest = read(manager.join("Cargo.toml"));
+    assert!(
+        manifest.contains(r#"request_type = "ChatRequest""#),
+        "{}",
+        manifest
+    );
+    assert!(
+        manifest.contains(r#"manager_type = "ChatManager""#),
+        "{}",
+        manifest
+    );
+    let saucer_core = checkout().canonicalize().unwrap().join("saucer-core");
+    assert!(
+        manifest.contains(&format!(
+            r#"saucer-core = {{ path = "{}" }}"#,
+            saucer_core.display()
+        )),
+        "{}",
+        manifest
+    );
+    assert!(read(manager.join("src/requests.rs")).contains("pub fn echo<Msg: 'static>("));
+    assert!(read(manager.join("src/command.rs"))
+        .lines()
+        .all(|l| l.starts_with("//")));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn new_requires_a_saucer_checkout() {
+    let dir = scratch("no-checkout");
+    let app = dir.join("tick-app");
+    let output = cargo_saucer(&["new", "app", app.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("pass `--saucer <dir>`"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(!app.exists());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn new_refuses_a_non_empty_directory() {
+    let dir = scratch("non-empty");
+    std::fs::write(dir.join("keep.txt"), "").unwrap();
+
+    let output = cargo_saucer(&[
+        "new",
+        "app",
+        dir.to_str().unwrap(),
+        "--saucer",
+        checkout().to_str().unwrap(),
+    ]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("already exists and is not empty"));
+    assert!(!dir.join("Cargo.toml").exists());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn check_summarizes_what_a_build_would_generate() {
+    let output = cargo_saucer(&["check", "--manifest-path", &fixture("mock_sub_app")]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let summary = stdout(&output);
+    assert!(summary.contains("package mock-sub-app"), "{}", summary);
+    assert!(
+        summary.contains("saucer-time-manager (TimeRequest)"),
+        "{}",
+        summary
+    );
+    assert!(
+        summary.contains("mock_sub_app::app (src/app.tea.rs)"),
+        "{}",
+        summary
+    );
+    assert!(
+        summary.contains("saucer_time_manager::command::every"),
+        "{}",
+        summary
+    );
+}
+
+#[test]
+fn check_finds_managers_from_workspace_and_git_dependencies() {
+    // A manager only reachable through git, so cargo checks it out elsewhere.
+    let remote = scratch("git-manager");
+    let manager = remote.join("saucer-chat-manager");
+    let output = cargo_saucer(&[
+        "new",
+        "manager",
+        manager.to_str().unwrap(),
+        "--saucer",
+        checkout().to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    git(&manager, &["init", "-q"]);
+    git(&manager, &["add", "."]);
+    git(&manager, &["commit", "-q", "-m", "chat manager"]);
+
+    // An app that inherits the time manager from its workspace.
+    let dir = scratch("workspace-deps");
+    let app = dir.join("tick-app");
+    let output = cargo_saucer(&[
+        "new",
+        "app",
+        app.to_str().unwrap(),
+        "--saucer",
+        checkout().to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let time_manager = checkout()
+        .canonicalize()
+        .unwrap()
+        .join("saucer-time-manager");
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        format!(
+            "[workspace]\nmembers = [\"tick-app\"]\nresolver = \"2\"\n\n[workspace.dependencies]\nsaucer-time-manager = {{ path = \"{}\" }}\n",
+            time_manager.display()
+        ),
+    )
+    .unwrap();
+    let manifest: Vec<String> = read(app.join("Cargo.toml"))
+        .lines()
+        .map(|line| {
+            if line.starts_with("saucer-time-manager = ") {
+                format!(
+                    "saucer-time-manager = {{ workspace = true }}\nsaucer-chat-manager = {{ git = \"file://{}\" }}",
+                    manager.display()
+                )
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    std::fs::write(app.join("Cargo.toml"), manifest.join("\n") + "\n").unwrap();
+
+    let manifest_path = app.join("Cargo.toml");
+    let output = cargo_saucer(&["check", "--manifest-path", manifest_path.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let summary = stdout(&output);
+    assert!(
+        summary.contains("saucer-time-manager (TimeRequest)"),
+        "{}",
+        summary
+    );
+    assert!(
+        summary.contains("saucer-chat-manager (ChatRequest)"),
+        "{}",
+        summary
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+    std::fs::remove_dir_all(remote).unwrap();
+}
+
+#[test]
+fn check_reports_every_problem_and_fails() {
+    let output = cargo_saucer(&["check", "--manifest-path", &fixture("mock_broken_app")]);
+    assert!(!output.status.success());
+
+    let diagnostics = stderr(&output);
+    assert!(
+        diagnostics.contains("error: found 6 problems"),
+        "{}",
+        diagnostics
+    );
+    assert!(diagnostics.contains("app.tea.rs:3:18"), "{}", diagnostics);
+}
+
+#[test]
+fn expand_prints_the_runtime_and_templates() {
+    let output = cargo_saucer(&[
+        "expand",
+        "--templates",
+        "--manifest-path",
+        &fixture("mock_sub_app"),
+    ]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let expanded = stdout(&output);
+    assert!(expanded.contains("pub mod mock_sub_app {"));
+    assert!(expanded.contains("// OUT_DIR/templates/mock_sub_app/app.tea.rs"));
+    assert!(expanded.contains("saucer_time_manager :: every as every_interval"));
+}
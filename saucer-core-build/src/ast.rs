@@ -35,12 +35,28 @@ pub struct ManagerInfo {
     pub variant: String,
     /// Request type (e.g., "TimeRequest")
     pub request_type: String,
+    /// Subscription type, if the manager offers subscriptions (e.g., "TimeSub")
+    pub subscription_type: Option<String>,
     /// Manager type (e.g., "TimeManager")
     pub manager_type: String,
     /// Self message type (e.g., "()" or "ChatManagerMsg")
     pub self_msg_type: String,
-    /// Path to dependency's lib.rs
+    /// Run the manager on its own blocking task instead of inside the runtime loop (`spawn = true`)
+    pub spawn: bool,
+    /// Root of the dependency's library target (usually src/lib.rs)
     pub lib_path: std::path::PathBuf,
+    /// Path to dependency's Cargo.toml
+    pub manifest_path: std::path::PathBuf,
+}
+
+impl ManagerInfo {
+    /// The file command helpers are read from, next to lib.rs.
+    pub fn requests_path(&self) -> std::path::PathBuf {
+        self.lib_path
+            .parent()
+            .expect("lib.rs has a parent")
+            .join("requests.rs")
+    }
 }
 
 /// Template info discovered from main.rs or transitive templates
@@ -63,6 +79,8 @@ pub struct TemplateInfo {
 #[derive(Debug, Clone)]
 pub struct RuntimeSpec {
     pub package_name: String,
+    /// `OUT_DIR`-relative directory the transformed templates are written to
+    pub templates_dir: String,
     pub managers: Vec<ManagerInfo>,
     pub effect_managers: Vec<ManagerInfo>,
     pub reconciler_manager: ManagerInfo,
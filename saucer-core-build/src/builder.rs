@@ -0,0 +1,289 @@
+//! Explicit build configuration for crates that outgrow the defaults of
+//! [`generate_runtime`](crate::generate_runtime).
+
+use std::path::{Path, PathBuf};
+
+use crate::error::BuildError;
+use crate::{resolve_spec, RuntimePlan};
+
+const DEFAULT_OUTPUT: &str = "runtime.rs";
+
+/// Configure and generate one runtime from a build script.
+///
+/// Every setting is optional; `RuntimeBuilder::new().generate()` is exactly
+/// [`generate_runtime`](crate::generate_runtime). Naming a distinct
+/// [`output`](Self::output) per runtime lets one crate generate several, e.g.
+/// a CLI and a test harness running different apps:
+///
+/// ```ignore
+/// use saucer_core::build::{ManagerConfig, RuntimeBuilder};
+///
+/// fn main() {
+///     RuntimeBuilder::new()
+///         .host("src/main.rs")
+///         .generate();
+///     RuntimeBuilder::new()
+///         .host("tests/harness.rs")
+///         .template_root("tests/apps")
+///         .manager(ManagerConfig::new("fake-clock", "ClockRequest", "ClockManager"))
+///         .output("harness_runtime.rs")
+///         .generate();
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeBuilder {
+    manifest_dir: Option<PathBuf>,
+    host: Option<PathBuf>,
+    template_roots: Vec<PathBuf>,
+    apps: Vec<String>,
+    managers: Vec<ManagerConfig>,
+    only_managers: Option<Vec<String>>,
+    reconciler: Option<String>,
+    output: Option<String>,
+}
+
+impl RuntimeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Crate to generate for. Defaults to `CARGO_MANIFEST_DIR`.
+    pub fn manifest_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.manifest_dir = Some(dir.into());
+        self
+    }
+
+    /// File that includes the runtime and imports its templates, relative to
+    /// the manifest directory.
+    ///
+    /// Defaults to whichever of `src/main.rs`, `src/lib.rs`, `src/bin/`,
+    /// `tests/` and `examples/` includes the output.
+    pub fn host(mut self, path: impl Into<PathBuf>) -> Self {
+        self.host = Some(path.into());
+        self
+    }
+
+    /// Directory the crate's own templates are looked up in, relative to the
+    /// manifest directory. Repeat to search several; the first match wins.
+    ///
+    /// Defaults to the host's directory.
+    pub fn template_root(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.template_roots.push(dir.into());
+        self
+    }
+
+    /// Name a root template of this crate (`app`, `screens::home`) instead
+    /// of reading the host's `use runtime::...` imports. Repeat for several.
+    ///
+    /// With apps named, no host is read: templates default to `src/` and the
+    /// reconciler to saucer-core's, unless set with [`reconciler`](Self::reconciler).
+    pub fn app(mut self, module: impl Into<String>) -> Self {
+        self.apps.push(module.into());
+        self
+    }
+
+    /// Effect manager whose reconciler the runtime is run with, instead of
+    /// the one passed to `Runtime::new` in the host.
+    pub fn reconciler(mut self, crate_name: impl Into<String>) -> Self {
+        self.reconciler = Some(crate_name.into());
+        self
+    }
+
+    /// Use only these effect managers rather than every dependency that
+    /// declares itself one. saucer-core is always included.
+    pub fn only_managers<I, S>(mut self, crate_names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.only_managers = Some(crate_names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Treat a dependency as an effect manager without reading
+    /// `[package.metadata.saucer]` from its Cargo.toml.
+    ///
+    /// Takes precedence over the dependency's own metadata, if it has any.
+    pub fn manager(mut self, manager: ManagerConfig) -> Self {
+        self.managers.push(manager);
+        self
+    }
+
+    /// File name to write under `OUT_DIR`. Defaults to `runtime.rs`.
+    ///
+    /// The host includes it as
+    /// `include!(concat!(env!("OUT_DIR"), "/<output>"))`; each output may be
+    /// included once.
+    pub fn output(mut self, file_name: impl Into<String>) -> Self {
+        self.output = Some(file_name.into());
+        self
+    }
+
+    /// Generate the runtime into `OUT_DIR`.
+    ///
+    /// Problems are reported to cargo as diagnostics (see [`BuildError::emit`])
+    /// and fail the build without a build-script backtrace.
+    pub fn generate(&self) {
+        if let Err(errors) = self.try_generate() {
+            for error in &errors {
+                error.emit();
+            }
+            std::process::exit(1);
+        }
+    }
+
+    /// Generate the runtime into `OUT_DIR`, returning its path, or every
+    /// problem found along the way.
+    pub fn try_generate(&self) -> Result<PathBuf, Vec<BuildError>> {
+        let out_dir =
+            std::env::var("OUT_DIR").expect("OUT_DIR not set - must be called from build.rs");
+
+        let mut watched = Vec::new();
+        let plan = self.resolve_watching(&mut watched);
+
+        // Tell cargo to rerun if relevant files change, even when checks failed,
+        // so callers that handle the errors themselves still get rebuilt.
+        // Template modules switch on `rust_analyzer`, so declare it as expected.
+        println!("cargo::rustc-check-cfg=cfg(rust_analyzer)");
+        for path in &watched {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+
+        let plan = plan?;
+        let output = plan.runtime_source()?;
+
+        // Write to OUT_DIR, each template next to the runtime that includes it.
+        for (include_path, code) in plan.template_sources() {
+            let path = Path::new(&out_dir).join(include_path);
+            std::fs::create_dir_all(path.parent().expect("template path has a parent"))
+                .unwrap_or_else(|e| panic!("Failed to create {:?}: {}", path.parent(), e));
+            std::fs::write(&path, code)
+                .unwrap_or_else(|e| panic!("Failed to write {:?}: {}", path, e));
+        }
+        let out_path = Path::new(&out_dir).join(self.output_name());
+
+        std::fs::write(&out_path, output)
+            .unwrap_or_else(|e| panic!("Failed to write {}: {}", self.output_name(), e));
+
+        Ok(out_path)
+    }
+
+    /// Run every check and discovery step without writing anything.
+    pub fn resolve(&self) -> Result<RuntimePlan, Vec<BuildError>> {
+        self.resolve_watching(&mut Vec::new())
+    }
+
+    fn resolve_watching(&self, watched: &mut Vec<PathBuf>) -> Result<RuntimePlan, Vec<BuildError>> {
+        let manifest_dir = match &self.manifest_dir {
+            Some(dir) => dir.clone(),
+            None => std::env::var("CARGO_MANIFEST_DIR")
+                .expect("CARGO_MANIFEST_DIR not set - must be called from build.rs")
+                .into(),
+        };
+        let output = self.output_name();
+        if output.contains(['/', '\\']) || !output.ends_with(".rs") || output == ".rs" {
+            return Err(vec![BuildError::new(format!(
+                "runtime output `{}` is not a plain `.rs` file name",
+                output
+            ))
+            .with_help(
+                "name a file directly under OUT_DIR, e.g. `cli_runtime.rs`",
+            )]);
+        }
+
+        let config = RuntimeConfig {
+            host: self.host.as_ref().map(|host| manifest_dir.join(host)),
+            template_roots: self
+                .template_roots
+                .iter()
+                .map(|root| manifest_dir.join(root))
+                .collect(),
+            apps: &self.apps,
+            managers: &self.managers,
+            only_managers: self.only_managers.as_deref(),
+            reconciler: self.reconciler.as_deref(),
+            output,
+        };
+        let spec = resolve_spec(&manifest_dir, &config, watched)?;
+        Ok(RuntimePlan { spec })
+    }
+
+    fn output_name(&self) -> &str {
+        self.output.as_deref().unwrap_or(DEFAULT_OUTPUT)
+    }
+}
+
+/// [`RuntimeBuilder`] settings with paths resolved against the manifest directory.
+pub(super) struct RuntimeConfig<'a> {
+    pub host: Option<PathBuf>,
+    pub template_roots: Vec<PathBuf>,
+    pub apps: &'a [String],
+    pub managers: &'a [ManagerConfig],
+    pub only_managers: Option<&'a [String]>,
+    pub reconciler: Option<&'a str>,
+    pub output: &'a str,
+}
+
+impl RuntimeConfig<'_> {
+    /// The `include!` a host uses for this runtime.
+    pub fn include_marker(&self) -> String {
+        format!("include!(concat!(env!(\"OUT_DIR\"), \"/{}\"))", self.output)
+    }
+
+    /// `OUT_DIR`-relative directory for this runtime's transformed templates.
+    /// The default runtime keeps `templates/`; others get a subdirectory named
+    /// after their output so runtimes sharing a template do not overwrite it.
+    pub fn templates_dir(&self) -> String {
+        match self.output {
+            DEFAULT_OUTPUT => "templates".to_string(),
+            output => format!("templates/{}", output.trim_end_matches(".rs")),
+        }
+    }
+}
+
+/// An effect manager declared from the build script rather than through its
+/// `[package.metadata.saucer]` block. Mirrors the metadata keys.
+#[derive(Debug, Clone)]
+pub struct ManagerConfig {
+    pub(super) crate_name: String,
+    pub(super) request_type: String,
+    pub(super) manager_type: String,
+    pub(super) subscription_type: Option<String>,
+    pub(super) self_msg_type: String,
+    pub(super) spawn: bool,
+}
+
+impl ManagerConfig {
+    /// `crate_name` is the dependency as declared in Cargo.toml.
+    pub fn new(
+        crate_name: impl Into<String>,
+        request_type: impl Into<String>,
+        manager_type: impl Into<String>,
+    ) -> Self {
+        Self {
+            crate_name: crate_name.into(),
+            request_type: request_type.into(),
+            manager_type: manager_type.into(),
+            subscription_type: None,
+            self_msg_type: "()".to_string(),
+            spawn: false,
+        }
+    }
+
+    pub fn subscription_type(mut self, ty: impl Into<String>) -> Self {
+        self.subscription_type = Some(ty.into());
+        self
+    }
+
+    /// Defaults to `()`.
+    pub fn self_msg_type(mut self, ty: impl Into<String>) -> Self {
+        self.self_msg_type = ty.into();
+        self
+    }
+
+    /// Run the manager on its own blocking task instead of inside the runtime loop.
+    pub fn spawn(mut self, spawn: bool) -> Self {
+        self.spawn = spawn;
+        self
+    }
+}
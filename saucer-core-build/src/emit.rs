@@ -0,0 +1,2124 @@
+use std::collections::{BTreeMap, HashSet};
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+
+use crate::ast::{ManagerInfo, PortSpec, RuntimeSpec, TemplateInfo};
+use crate::error::BuildError;
+use crate::hygiene::crate_to_module_name;
+use crate::ports;
+use crate::request::{generate_request_enum, generate_self_msg_type, generate_subscription_enum};
+
+/// Produce the final generated runtime source as a string.
+///
+/// Template modules `include!` their transformed source from the paths given
+/// by [`template_include_path`]; the caller writes those files.
+///
+/// Panics with the rendered diagnostics if a helper cannot be generated; see
+/// [`try_generate_runtime_source`].
+pub fn generate_runtime_source(spec: &RuntimeSpec) -> String {
+    try_generate_runtime_source(spec).unwrap_or_else(|errors| {
+        let rendered: Vec<String> = errors.iter().map(BuildError::render).collect();
+        panic!("{}", rendered.join("\n\n"))
+    })
+}
+
+/// Produce the final generated runtime source, or every helper that could not
+/// be generated from its manager's `requests.rs`.
+pub fn try_generate_runtime_source(spec: &RuntimeSpec) -> Result<String, Vec<BuildError>> {
+    generate_source(spec, TemplateSource::Included(&spec.templates_dir))
+}
+
+/// How template modules get their transformed source.
+#[derive(Clone, Copy)]
+pub(crate) enum TemplateSource<'a> {
+    /// `include!` from files under this `OUT_DIR`-relative directory.
+    Included(&'a str),
+    /// Embedded in the runtime, for callers without an `OUT_DIR`.
+    Inline,
+}
+
+pub(crate) fn generate_source(
+    spec: &RuntimeSpec,
+    template_source: TemplateSource,
+) -> Result<String, Vec<BuildError>> {
+    let mut errors = Vec::new();
+    let manager_imports = generate_manager_imports(&spec.effect_managers);
+    let request_enum = generate_request_enum(&spec.effect_managers, &spec.all_ports);
+    let self_msg_type = generate_self_msg_type(&spec.effect_managers);
+    let subscription_enum = generate_subscription_enum(&spec.effect_managers);
+    let cmd_type = generate_cmd_type();
+    let sub_type = generate_sub_type();
+    let command_helpers = generate_command_helpers(
+        &spec.effect_managers,
+        &spec.used_helpers,
+        &spec.all_ports,
+        &mut errors,
+    );
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let runtime_state = generate_runtime_state(&spec.effect_managers);
+    // Derive the app message path. Ports only exist on the root template, so prefer them;
+    // otherwise fall back to the first template module (root) to avoid self-recursive aliases.
+    // Local templates are named relative to the runtime (`sync` globs its parent), so the
+    // runtime module can be mounted anywhere in the crate.
+    let msg_ty: TokenStream =
+        if let Some((_, crate_ident, module_ident)) = spec.ports_with_paths.first() {
+            if crate_ident.to_string() == crate_to_module_name(&spec.package_name) {
+                quote! { #crate_ident::#module_ident::Msg }
+            } else {
+                quote! { ::#crate_ident::#module_ident::Msg }
+            }
+        } else if let Some(template) = spec.templates.first() {
+            let crate_ident = format_ident!("{}", crate_to_module_name(&template.crate_name));
+            let module_ident = format_ident!("{}", template.module_name);
+            if crate_ident.to_string() == crate_to_module_name(&spec.package_name) {
+                quote! { #crate_ident::#module_ident::Msg }
+            } else {
+                quote! { ::#crate_ident::#module_ident::Msg }
+            }
+        } else {
+            quote! { AppMsg }
+        };
+
+    let ports_struct = generate_ports_struct(
+        &spec.ports_with_paths,
+        &msg_ty,
+        &crate_to_module_name(&spec.package_name),
+    );
+    let runtime = generate_runtime_struct(
+        &spec.effect_managers,
+        &spec.reconciler_manager,
+        &spec.ports_with_paths,
+        spec.has_outgoing_ports,
+        &msg_ty,
+    );
+    let test_runtime = generate_test_runtime_struct();
+    let debugger = generate_debugger();
+
+    let template_modules = build_template_modules(
+        &spec.templates,
+        template_source,
+        &spec.transformed_templates,
+        &spec.effect_managers,
+        &spec.used_helpers,
+        &spec.package_name,
+        &mut errors,
+    );
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let tracked_files = match template_source {
+        TemplateSource::Included(_) => quote! {},
+        TemplateSource::Inline => generate_manager_tracking(&spec.effect_managers),
+    };
+
+    let runtime_module = quote! {
+        #[allow(unused_imports)]
+        pub mod sync {
+            use super::*;
+            use saucer_core::{Observation, ObserverFn};
+            #runtime
+            #test_runtime
+            #debugger
+        }
+    };
+
+    let output_tokens = quote! {
+        // Generated runtime module - do not edit manually
+
+        #manager_imports
+        #request_enum
+        #self_msg_type
+        #subscription_enum
+        #cmd_type
+        #sub_type
+        #command_helpers
+        #runtime_state
+        #ports_struct
+        #runtime_module
+        #(#template_modules)*
+        #tracked_files
+    };
+
+    let parsed: syn::File = syn::parse2(output_tokens).expect("Generated runtime did not parse");
+    Ok(prettyplease::unparse(&parsed))
+}
+
+fn build_template_modules(
+    templates: &[TemplateInfo],
+    template_source: TemplateSource,
+    transformed_templates: &[(String, String)],
+    managers: &[ManagerInfo],
+    used_helpers: &[(String, String)],
+    package_name: &str,
+    errors: &mut Vec<BuildError>,
+) -> Vec<TokenStream> {
+    let editor_shadows = generate_editor_shadows(templates, managers, used_helpers, package_name);
+    let mut grouped: BTreeMap<String, Vec<TokenStream>> = BTreeMap::new();
+    for (template, (_name, code)) in templates.iter().zip(transformed_templates.iter()) {
+        let crate_mod = crate_to_module_name(&template.crate_name);
+        let outgoing: Vec<PortSpec> = template
+            .ports
+            .iter()
+            .cloned()
+            .filter(|p| matches!(p, PortSpec::Outgoing(_)))
+            .collect();
+        let module_ts = match template_source {
+            TemplateSource::Included(templates_dir) => generate_template_module_with_source(
+                template,
+                templates_dir,
+                code.clone(),
+                &outgoing,
+                &editor_shadows,
+            ),
+            TemplateSource::Inline => {
+                match generate_inline_template_module(template, code, &outgoing) {
+                    Ok(module) => module,
+                    Err(e) => {
+                        errors.push(e);
+                        continue;
+                    }
+                }
+            }
+        };
+        grouped.entry(crate_mod).or_default().push(module_ts);
+    }
+
+    grouped
+        .into_iter()
+        .map(|(crate_mod, mods)| {
+            let crate_ident = format_ident!("{}", crate_mod);
+            quote! {
+                pub mod #crate_ident {
+                    #(#mods)*
+                }
+            }
+        })
+        .collect()
+}
+
+fn generate_manager_imports(_managers: &[ManagerInfo]) -> TokenStream {
+    // All manager items are referenced with fully-qualified paths (`crate_name::Type`),
+    // so we don't need glob imports that trigger unused-import warnings.
+    quote! {}
+}
+
+fn generate_cmd_type() -> TokenStream {
+    let cmd_ext = quote! {
+        #[allow(dead_code)]
+        pub trait CmdExt<Msg> {
+            #[allow(dead_code)]
+            fn map<Msg2>(self, f: impl Fn(Msg) -> Msg2 + Send + Sync + Clone + 'static) -> Cmd<Msg2>;
+        }
+
+        #[allow(dead_code)]
+        impl<Msg: 'static> CmdExt<Msg> for Cmd<Msg> {
+            #[allow(dead_code)]
+            fn map<Msg2>(self, f: impl Fn(Msg) -> Msg2 + Send + Sync + Clone + 'static) -> Cmd<Msg2> {
+                saucer_core::CoreCmd(
+                    self.into_inner()
+                        .into_iter()
+                        .map(|req| req.map(f.clone()))
+                        .collect()
+                )
+            }
+        }
+    };
+
+    quote! {
+        pub type Cmd<Msg> = saucer_core::CoreCmd<Request<Msg>>;
+        #cmd_ext
+    }
+}
+
+fn generate_sub_type() -> TokenStream {
+    let sub_ext = quote! {
+        #[allow(dead_code)]
+        pub trait SubExt<Msg> {
+            #[allow(dead_code)]
+            fn map<Msg2>(self, f: impl Fn(Msg) -> Msg2 + Send + Sync + Clone + 'static) -> Sub<Msg2>;
+        }
+
+        #[allow(dead_code)]
+        impl<Msg: 'static> SubExt<Msg> for Sub<Msg> {
+            #[allow(dead_code)]
+            fn map<Msg2>(self, f: impl Fn(Msg) -> Msg2 + Send + Sync + Clone + 'static) -> Sub<Msg2> {
+                saucer_core::CoreSub(
+                    self.into_inner()
+                        .into_iter()
+                        .map(|sub| sub.map(f.clone()))
+                        .collect()
+                )
+            }
+        }
+    };
+
+    quote! {
+        pub type Sub<Msg> = saucer_core::CoreSub<Subscription<Msg>>;
+        #sub_ext
+    }
+}
+
+fn generate_command_helpers(
+    managers: &[ManagerInfo],
+    used_helpers: &[(String, String)],
+    ports: &[PortSpec],
+    errors: &mut Vec<BuildError>,
+) -> TokenStream {
+    // Core helpers live under `core::` only if used
+    let core_mod = if used_helpers
+        .iter()
+        .any(|(m, h)| m == "saucer_core" && h == "shutdown")
+    {
+        quote! {
+            pub mod core {
+                use super::{Cmd, Request};
+                pub fn shutdown<Msg>() -> Cmd<Msg> {
+                    saucer_core::CoreCmd::single(Request::Core(saucer_core::shutdown()))
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let manager_modules: Vec<_> = managers
+        .iter()
+        .map(|m| {
+            let module_ident = format_ident!("{}", m.module_name);
+            let helpers = generate_typed_helpers_for_manager(m, used_helpers, errors);
+            if helpers.is_empty() {
+                quote! {}
+            } else {
+                quote! {
+                    pub mod #module_ident {
+                        use super::*;
+                        #helpers
+                    }
+                }
+            }
+        })
+        .collect();
+
+    let port_helpers: Vec<_> = ports
+        .iter()
+        .filter_map(|p| match p {
+            PortSpec::Outgoing(p) => Some(p),
+            _ => None,
+        })
+        .map(|p| {
+            let fn_ident = format_ident!("{}", p.name);
+            let payload_ty = ports::payload_type_tokens(&PortSpec::Outgoing(p.clone()));
+            let params: Vec<_> = p
+                .args
+                .iter()
+                .map(|(name, ty)| {
+                    let ident = format_ident!("{}", name);
+                    let ty_ts: TokenStream = ty.parse().unwrap();
+                    quote! { #ident: #ty_ts }
+                })
+                .collect();
+
+            let call_args: Vec<_> = p
+                .args
+                .iter()
+                .map(|(name, _)| format_ident!("{}", name))
+                .collect();
+
+            let variant_ident = format_ident!("{}", ports::to_camel(&p.name));
+            let value_binding = if p.args.is_empty() {
+                quote! { let value: #payload_ty = (); }
+            } else if p.args.len() == 1 {
+                let arg = call_args.first().unwrap();
+                quote! { let value: #payload_ty = #arg; }
+            } else {
+                quote! { let value: #payload_ty = ( #(#call_args),* ); }
+            };
+
+            quote! {
+                pub fn #fn_ident<Msg>(#(#params),*) -> Cmd<Msg> {
+                    #value_binding
+                    saucer_core::CoreCmd::single(Request::Ports(PortsRequest::#variant_ident { value }, std::marker::PhantomData))
+                }
+            }
+        })
+        .collect();
+
+    quote! {
+        #core_mod
+        #(#manager_modules)*
+        #(#port_helpers)*
+    }
+}
+
+/// Validate every used helper against its manager's `requests.rs` without
+/// generating the runtime, so helper problems are reported with the rest.
+pub(crate) fn check_helpers(
+    managers: &[ManagerInfo],
+    used_helpers: &[(String, String)],
+    errors: &mut Vec<BuildError>,
+) {
+    for manager in managers {
+        generate_typed_helpers_for_manager(manager, used_helpers, errors);
+    }
+}
+
+fn generate_typed_helpers_for_manager(
+    manager: &ManagerInfo,
+    used_helpers: &[(String, String)],
+    errors: &mut Vec<BuildError>,
+) -> TokenStream {
+    let module_ident = format_ident!("{}", manager.module_name);
+    let variant_ident = format_ident!("{}", manager.variant);
+    let needed_for_mgr: Vec<String> = used_helpers
+        .iter()
+        .filter(|(mgr, _)| mgr == &manager.module_name)
+        .map(|(_, h)| h.clone())
+        .collect();
+
+    let mut helper_tokens = Vec::new();
+    let mut import_idents = Vec::new();
+
+    for helper_name in needed_for_mgr {
+        match parse_and_generate_helper(manager, &helper_name, &module_ident, &variant_ident) {
+            Ok((helper_ts, needed)) => {
+                helper_tokens.push(helper_ts);
+                import_idents.extend(needed);
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let import_idents: Vec<_> = import_idents
+        .into_iter()
+        .filter(|id| seen.insert(id.to_string()))
+        .collect();
+
+    let imports = if import_idents.is_empty() {
+        quote! {}
+    } else {
+        quote! { use ::#module_ident::{ #(#import_idents),* }; }
+    };
+
+    quote! {
+        #imports
+        #(#helper_tokens)*
+    }
+}
+
+fn parse_and_generate_helper(
+    manager: &ManagerInfo,
+    helper_name: &str,
+    module_ident: &proc_macro2::Ident,
+    variant_ident: &proc_macro2::Ident,
+) -> Result<(TokenStream, Vec<syn::Ident>), BuildError> {
+    use syn::{FnArg, Item, ItemFn, Pat, ReturnType};
+
+    // Enforce a consistent effect-manager structure: helpers must live in requests.rs
+    // (breadcrumb command.rs is for fictional imports only). We no longer scan lib.rs
+    // for helpers to avoid surprises and keep codegen deterministic.
+    let requests_path = manager.requests_path();
+
+    let req_source = std::fs::read_to_string(&requests_path).map_err(|e| {
+        BuildError::io(&requests_path, &e).with_help(format!(
+            "helper `{}` must be declared in {}'s requests.rs",
+            helper_name, manager.crate_name
+        ))
+    })?;
+    let file_ast: syn::File =
+        syn::parse_str(&req_source).map_err(|e| BuildError::parse(&requests_path, &e))?;
+    let error = |message: String| BuildError::new(message).in_file(&requests_path);
+
+    let func: ItemFn = file_ast
+        .items
+        .iter()
+        .find_map(|item| match item {
+            Item::Fn(f)
+                if f.sig.ident == helper_name && matches!(f.vis, syn::Visibility::Public(_)) =>
+            {
+                Some(f.clone())
+            }
+            _ => None,
+        })
+        .ok_or_else(|| {
+            error(format!(
+                "helper `{}` is imported from `{}::command` but not declared here",
+                helper_name, manager.module_name
+            ))
+            .with_help(format!(
+                "add `pub fn {}<Msg>(...)` to requests.rs",
+                helper_name
+            ))
+        })?;
+
+    // Ensure return type matches the manager's request_type<Msg> (or subscription_type<Msg>)
+    let returns_subscription = match &func.sig.output {
+        ReturnType::Type(_, ty) => {
+            if return_type_matches_request(ty, &manager.request_type) {
+                false
+            } else if manager
+                .subscription_type
+                .as_ref()
+                .is_some_and(|sub_type| return_type_matches_request(ty, sub_type))
+            {
+                true
+            } else {
+                let expected = match &manager.subscription_type {
+                    Some(sub_type) => format!("`{}` or `{}`", manager.request_type, sub_type),
+                    None => format!("`{}`", manager.request_type),
+                };
+                return Err(error(format!(
+                    "helper `{}` must return {}; found `{}`",
+                    helper_name,
+                    expected,
+                    ty.to_token_stream()
+                ))
+                .at(ty));
+            }
+        }
+        ReturnType::Default => {
+            return Err(error(format!(
+                "helper `{}` must return a request type",
+                helper_name
+            ))
+            .at(&func.sig))
+        }
+    };
+
+    // Collect inputs and arg idents
+    let mut params = Vec::new();
+    let mut args = Vec::new();
+    let mut type_collector = TypeIdentCollector::default();
+    for arg in &func.sig.inputs {
+        match arg {
+            FnArg::Receiver(receiver) => {
+                return Err(
+                    error(format!("helper `{}` should not take self", helper_name)).at(receiver),
+                )
+            }
+            FnArg::Typed(pat_type) => {
+                if let Pat::Ident(pat_ident) = &*pat_type.pat {
+                    let ty = &pat_type.ty;
+                    type_collector.collect_type_idents(ty);
+                    params.push(quote! { #pat_ident : #ty });
+                    let ident = &pat_ident.ident;
+                    args.push(quote! { #ident });
+                } else {
+                    return Err(error(format!(
+                        "helper `{}` has an unsupported pattern argument",
+                        helper_name
+                    ))
+                    .at(&pat_type.pat));
+                }
+            }
+        }
+    }
+
+    // Ensure Msg generic exists
+    if !func.sig.generics.type_params().any(|tp| tp.ident == "Msg") {
+        return Err(error(format!(
+            "helper `{}` must be generic over Msg to generate Cmd<Msg>",
+            helper_name
+        ))
+        .at(&func.sig.ident));
+    }
+
+    let fn_ident = format_ident!("{}", helper_name);
+    let generics = &func.sig.generics;
+    let where_clause = &func.sig.generics.where_clause;
+
+    let needed_idents = type_collector.into_idents_except(&["Msg"]);
+
+    let module_path = quote! { ::#module_ident };
+    let helper_tokens = if returns_subscription {
+        quote! {
+            pub fn #fn_ident #generics (#(#params),*) -> Sub<Msg> #where_clause {
+                saucer_core::CoreSub::single(Subscription::#variant_ident(#module_path::#fn_ident(#(#args),*)))
+            }
+        }
+    } else {
+        quote! {
+            pub fn #fn_ident #generics (#(#params),*) -> Cmd<Msg> #where_clause {
+                saucer_core::CoreCmd::single(Request::#variant_ident(#module_path::#fn_ident(#(#args),*)))
+            }
+        }
+    };
+
+    Ok((helper_tokens, needed_idents))
+}
+
+#[derive(Default)]
+struct TypeIdentCollector {
+    idents: Vec<syn::Ident>,
+}
+
+impl TypeIdentCollector {
+    fn collect_type_idents(&mut self, ty: &syn::Type) {
+        use syn::{
+            GenericArgument, PathArguments, ReturnType as SynReturnType, Type, TypeParamBound,
+            TypePath,
+        };
+        match ty {
+            Type::Path(TypePath { qself: None, path }) => {
+                if let Some(first) = path.segments.first() {
+                    self.idents.push(first.ident.clone());
+                }
+                for segment in &path.segments {
+                    match &segment.arguments {
+                        PathArguments::AngleBracketed(args) => {
+                            for arg in &args.args {
+                                if let GenericArgument::Type(t) = arg {
+                                    self.collect_type_idents(t);
+                                }
+                            }
+                        }
+                        PathArguments::Parenthesized(paren) => {
+                            for input in &paren.inputs {
+                                self.collect_type_idents(input);
+                            }
+                            if let SynReturnType::Type(_, ret_ty) = &paren.output {
+                                self.collect_type_idents(ret_ty);
+                            }
+                        }
+                        PathArguments::None => {}
+                    }
+                }
+            }
+            Type::Reference(r) => self.collect_type_idents(&r.elem),
+            Type::Tuple(t) => {
+                for elem in &t.elems {
+                    self.collect_type_idents(elem);
+                }
+            }
+            Type::Paren(p) => self.collect_type_idents(&p.elem),
+            Type::ImplTrait(it) => {
+                for b in &it.bounds {
+                    if let TypeParamBound::Trait(tb) = b {
+                        self.collect_trait_bound_idents(tb);
+                    }
+                }
+            }
+            Type::TraitObject(to) => {
+                for b in &to.bounds {
+                    if let TypeParamBound::Trait(tb) = b {
+                        self.collect_trait_bound_idents(tb);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn collect_trait_bound_idents(&mut self, tb: &syn::TraitBound) {
+        use syn::{GenericArgument, PathArguments, ReturnType as SynReturnType};
+        if let Some(first) = tb.path.segments.first() {
+            self.idents.push(first.ident.clone());
+        }
+        for segment in &tb.path.segments {
+            match &segment.arguments {
+                PathArguments::AngleBracketed(args) => {
+                    for arg in &args.args {
+                        if let GenericArgument::Type(t) = arg {
+                            self.collect_type_idents(t);
+                        }
+                    }
+                }
+                PathArguments::Parenthesized(paren) => {
+                    for input in &paren.inputs {
+                        self.collect_type_idents(input);
+                    }
+                    if let SynReturnType::Type(_, ret_ty) = &paren.output {
+                        self.collect_type_idents(ret_ty);
+                    }
+                }
+                PathArguments::None => {}
+            }
+        }
+    }
+
+    fn into_idents_except(self, skip: &[&str]) -> Vec<syn::Ident> {
+        let mut skip_set: HashSet<String> = skip.iter().map(|s| s.to_string()).collect();
+        // Standard/prelude types and traits we should not try to import from the manager crate
+        let std_names = [
+            "Into",
+            "From",
+            "Fn",
+            "FnOnce",
+            "FnMut",
+            "Send",
+            "Sync",
+            "Clone",
+            "Copy",
+            "Debug",
+            "Display",
+            "String",
+            "Result",
+            "Option",
+            "Vec",
+            "Box",
+            "Borrow",
+            "AsRef",
+            "PartialEq",
+            "Eq",
+            "PartialOrd",
+            "Ord",
+            "Hash",
+            "Iterator",
+            // primitives
+            "f64",
+            "f32",
+            "i64",
+            "i32",
+            "i16",
+            "i8",
+            "u64",
+            "u32",
+            "u16",
+            "u8",
+            "isize",
+            "usize",
+            "bool",
+            "char",
+            "str",
+        ];
+        skip_set.extend(std_names.iter().map(|s| s.to_string()));
+        let mut seen = HashSet::new();
+        self.idents
+            .into_iter()
+            .filter(|id| {
+                let name = id.to_string();
+                !skip_set.contains(&name) && seen.insert(name)
+            })
+            .collect()
+    }
+}
+
+fn return_type_matches_request(ty: &syn::Type, request_ident: &str) -> bool {
+    if let syn::Type::Path(tp) = ty {
+        if let Some(last) = tp.path.segments.last() {
+            return last.ident == request_ident;
+        }
+    }
+    false
+}
+
+fn generate_runtime_state(_managers: &[ManagerInfo]) -> TokenStream {
+    quote! {}
+}
+
+fn generate_ports_struct(
+    ports_with_paths: &[(PortSpec, proc_macro2::Ident, proc_macro2::Ident)],
+    msg_ty: &TokenStream,
+    local_crate: &str,
+) -> TokenStream {
+    if ports_with_paths.is_empty() {
+        return quote! {};
+    }
+
+    // Define per-port structs
+    let incoming_structs: Vec<_> = ports_with_paths
+        .iter()
+        .filter_map(|(p, _, _)| match p {
+            PortSpec::Incoming(p) => Some(p),
+            _ => None,
+        })
+        .map(|p| {
+            let struct_ident = format_ident!("{}PortIn", ports::to_camel(&p.name));
+            let send_args: Vec<_> = p
+                .args
+                .iter()
+                .map(|(n, t)| {
+                    let ident = format_ident!("{}", n);
+                    let ty: TokenStream = t.parse().unwrap();
+                    quote! { #ident: #ty }
+                })
+                .collect();
+            let call_idents: Vec<_> = p.args.iter().map(|(n, _)| format_ident!("{}", n)).collect();
+            let send_sig = if send_args.is_empty() {
+                quote! {
+                    pub fn send(&self) {
+                        let app_msg: AppMsg = (self.constructor)().into();
+                        self.router.send_to_app(app_msg);
+                    }
+                }
+            } else {
+                quote! {
+                    pub fn send(&self, #(#send_args),*) {
+                        let app_msg: AppMsg = (self.constructor)(#(#call_idents),*).into();
+                        self.router.send_to_app(app_msg);
+                    }
+                }
+            };
+            let fn_type: TokenStream = {
+                let tys: Vec<_> = p
+                    .args
+                    .iter()
+                    .map(|(_, t)| t.parse::<TokenStream>().unwrap())
+                    .collect();
+                if tys.is_empty() {
+                    quote! { fn() -> #msg_ty }
+                } else {
+                    quote! { fn(#(#tys),*) -> #msg_ty }
+                }
+            };
+            quote! {
+                #[derive(Clone)]
+                pub struct #struct_ident<SelfMsg, AppMsg>
+                where
+                    SelfMsg: Send + Clone + 'static,
+                    AppMsg: Clone + Send + 'static + From<#msg_ty>,
+                {
+                    router: saucer_core::Router<AppMsg, SelfMsg>,
+                    constructor: #fn_type,
+                }
+                impl<SelfMsg, AppMsg> #struct_ident<SelfMsg, AppMsg>
+                where
+                    SelfMsg: Send + Clone + 'static,
+                    AppMsg: Clone + Send + 'static + From<#msg_ty>,
+                {
+                    #send_sig
+                }
+            }
+        })
+        .collect();
+
+    let outgoing_structs: Vec<_> = ports_with_paths
+        .iter()
+        .filter_map(|(p, _, _)| match p {
+            PortSpec::Outgoing(p) => Some(p),
+            _ => None,
+        })
+        .map(|p| {
+            let struct_ident = format_ident!("{}PortOut", ports::to_camel(&p.name));
+            let inner_ident = format_ident!("{}PortOutInner", ports::to_camel(&p.name));
+            let ty = ports::payload_type_tokens(&PortSpec::Outgoing(p.clone()));
+            quote! {
+                #[derive(Clone)]
+                pub struct #struct_ident
+                where
+                    #ty: Send + Clone + 'static,
+                {
+                    inner: std::sync::Arc<#inner_ident>,
+                }
+
+                struct #inner_ident {
+                    tx: tokio::sync::mpsc::UnboundedSender<#ty>,
+                    subscribers: std::sync::Mutex<Vec<Box<dyn Fn(#ty) + Send + 'static>>>,
+                }
+
+                impl #struct_ident
+                where
+                    #ty: Send + Clone + 'static,
+                {
+                    pub fn new() -> (Self, tokio::sync::mpsc::UnboundedReceiver<#ty>) {
+                        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<#ty>();
+                        (
+                            Self {
+                                inner: std::sync::Arc::new(#inner_ident {
+                                    tx,
+                                    subscribers: std::sync::Mutex::new(Vec::new()),
+                                }),
+                            },
+                            rx,
+                        )
+                    }
+
+                    pub fn subscribe<F>(&self, f: F)
+                    where
+                        F: Fn(#ty) + Send + 'static,
+                    {
+                        self.inner
+                            .subscribers
+                            .lock()
+                            .expect("port subscriber mutex poisoned")
+                            .push(Box::new(f));
+                    }
+
+                    pub fn dispatch(&self, v: #ty) {
+                        let _ = self.inner.tx.send(v);
+                    }
+
+                    pub fn deliver(&self, v: #ty) {
+                        let subs = self.inner
+                            .subscribers
+                            .lock()
+                            .expect("port subscriber mutex poisoned");
+                        for handler in subs.iter() {
+                            handler(v.clone());
+                        }
+                    }
+                }
+
+                impl Default for #struct_ident
+                where
+                    #ty: Send + Clone + 'static,
+                {
+                    fn default() -> Self {
+                        let (port, _) = Self::new();
+                        port
+                    }
+                }
+            }
+        })
+        .collect();
+
+    // Ports struct fields
+    let incoming_fields: Vec<_> = ports_with_paths
+        .iter()
+        .filter_map(|(p, _, _)| match p {
+            PortSpec::Incoming(p) => Some(p),
+            _ => None,
+        })
+        .map(|p| {
+            let field = format_ident!("{}", p.name);
+            let ty = format_ident!("{}PortIn", ports::to_camel(&p.name));
+            quote! { pub #field: #ty<SelfMsg, AppMsg>, }
+        })
+        .collect();
+
+    let outgoing_fields: Vec<_> = ports_with_paths
+        .iter()
+        .filter_map(|(p, _, _)| match p {
+            PortSpec::Outgoing(p) => Some(p),
+            _ => None,
+        })
+        .map(|p| {
+            let field = format_ident!("{}", p.name);
+            let ty = format_ident!("{}PortOut", ports::to_camel(&p.name));
+            quote! { pub #field: #ty, }
+        })
+        .collect();
+
+    let outgoing_field_inits: Vec<_> = ports_with_paths
+        .iter()
+        .filter_map(|(p, _, _)| match p {
+            PortSpec::Outgoing(p) => Some(p),
+            _ => None,
+        })
+        .map(|p| {
+            let field = format_ident!("{}", p.name);
+            quote! { #field, }
+        })
+        .collect();
+
+    let outgoing_receiver_fields: Vec<_> = ports_with_paths
+        .iter()
+        .filter_map(|(p, _, _)| match p {
+            PortSpec::Outgoing(p) => Some(p),
+            _ => None,
+        })
+        .map(|p| {
+            let field = format_ident!("{}_rx", p.name);
+            let ty: TokenStream = ports::payload_type_tokens(&PortSpec::Outgoing(p.clone()));
+            quote! { pub #field: tokio::sync::mpsc::UnboundedReceiver<#ty>, }
+        })
+        .collect();
+
+    // Ports struct initializers
+    let incoming_inits: Vec<_> = ports_with_paths
+        .iter()
+        .filter_map(|(p, crate_ident, module_ident)| match p {
+            PortSpec::Incoming(p) => Some((p, crate_ident, module_ident)),
+            _ => None,
+        })
+        .map(|(p, crate_ident, module_ident)| {
+            let field = format_ident!("{}", p.name);
+            let ty = format_ident!("{}PortIn", ports::to_camel(&p.name));
+            let original_fn = if crate_ident.to_string() == local_crate {
+                quote! { #crate_ident::#module_ident::#field }
+            } else {
+                quote! { ::#crate_ident::#module_ident::#field }
+            };
+            quote! {
+                #field: #ty {
+                    router: router.clone(),
+                    constructor: #original_fn,
+                },
+            }
+        })
+        .collect();
+
+    let outgoing_inits: Vec<_> = ports_with_paths
+        .iter()
+        .filter_map(|(p, _, _)| match p {
+            PortSpec::Outgoing(p) => Some(p),
+            _ => None,
+        })
+        .map(|p| {
+            let field = format_ident!("{}", p.name);
+            let ty = format_ident!("{}PortOut", ports::to_camel(&p.name));
+            let rx_field = format_ident!("{}_rx", p.name);
+            quote! { let (#field, #rx_field) = #ty::new(); }
+        })
+        .collect();
+
+    let outgoing_receiver_inits: Vec<_> = ports_with_paths
+        .iter()
+        .filter_map(|(p, _, _)| match p {
+            PortSpec::Outgoing(p) => Some(p),
+            _ => None,
+        })
+        .map(|p| {
+            let field = format_ident!("{}_rx", p.name);
+            quote! { #field, }
+        })
+        .collect();
+
+    quote! {
+        #(#incoming_structs)*
+        #(#outgoing_structs)*
+
+        #[derive(Clone)]
+        pub struct Ports<SelfMsg, AppMsg>
+        where
+            SelfMsg: Send + Clone + 'static,
+            AppMsg: Clone + Send + 'static + From<#msg_ty>,
+        {
+            #(#incoming_fields)*
+            #(#outgoing_fields)*
+        }
+
+        impl<SelfMsg, AppMsg> Ports<SelfMsg, AppMsg>
+        where
+            SelfMsg: Send + Clone + 'static,
+            AppMsg: Clone + Send + 'static + From<#msg_ty>,
+        {
+            fn new(router: saucer_core::Router<AppMsg, SelfMsg>) -> (Self, PortsReceivers) {
+                #(#outgoing_inits)*
+
+                let ports = Self {
+                    #(#incoming_inits)*
+                    #(#outgoing_field_inits)*
+                };
+
+                let receivers = PortsReceivers {
+                    #(#outgoing_receiver_inits)*
+                };
+
+                (ports, receivers)
+            }
+        }
+
+        pub struct PortsReceivers {
+            #(#outgoing_receiver_fields)*
+        }
+    }
+}
+
+fn generate_runtime_struct(
+    effect_managers: &[ManagerInfo],
+    reconciler_manager: &ManagerInfo,
+    ports_with_paths: &[(PortSpec, proc_macro2::Ident, proc_macro2::Ident)],
+    has_outgoing_ports: bool,
+    msg_ty: &TokenStream,
+) -> TokenStream {
+    let has_ports = !ports_with_paths.is_empty();
+
+    // Each manager gets a router typed to its own self-message, over a private
+    // channel. Self-messages of managers listed in `SelfMsg` are forwarded into
+    // the shared queue; `()` managers have no variant there, so theirs go
+    // straight back to the manager.
+    let router_decls: Vec<_> = effect_managers
+        .iter()
+        .map(|m| {
+            let router_ident = format_ident!("{}_router", m.variant.to_lowercase());
+            let self_rx_ident = format_ident!("{}_self_rx", m.variant.to_lowercase());
+            quote! {
+                let (#router_ident, mut #self_rx_ident) = {
+                    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+                    (saucer_core::Router::new(self.app_tx.clone(), tx), rx)
+                };
+            }
+        })
+        .collect();
+
+    let self_msg_forward_arms: Vec<_> = effect_managers
+        .iter()
+        .map(|m| {
+            let variant_ident = format_ident!("{}", m.variant);
+            let self_rx_ident = format_ident!("{}_self_rx", m.variant.to_lowercase());
+            if m.self_msg_type != "()" {
+                return quote! {
+                    Some(msg) = #self_rx_ident.recv() => {
+                        let _ = self.self_tx.send(SelfMsg::#variant_ident(msg));
+                    }
+                };
+            }
+            let handle = if m.spawn {
+                let input_ident = format_ident!("{}_input", m.variant.to_lowercase());
+                quote! {
+                    let _ = #input_ident.send(saucer_core::ManagerInput::SelfMsg(msg));
+                }
+            } else {
+                let router_ident = format_ident!("{}_router", m.variant.to_lowercase());
+                let manager_ident = format_ident!("{}_manager", m.variant.to_lowercase());
+                let state_ident = format_ident!("{}_state", m.variant.to_lowercase());
+                quote! {
+                    #state_ident = saucer_core::EffectManager::on_self_msg(
+                        &#manager_ident,
+                        &#router_ident,
+                        #state_ident,
+                        msg,
+                    );
+                }
+            };
+            quote! {
+                Some(msg) = #self_rx_ident.recv() => {
+                    #handle
+                }
+            }
+        })
+        .collect();
+
+    // Inline managers keep their state in the runtime loop. Spawned managers
+    // (`spawn = true`) own it on the blocking pool, so a slow `on_effects`
+    // can't stall `update`/`view`; the runtime only forwards `ManagerInput`s
+    // and the task ends once the runtime drops its sender.
+    // Apps can replace `EffectManager::init` per manager (e.g. to start the
+    // time manager on a virtual clock); the closure runs where `init` would.
+    let state_init_fields: Vec<_> = effect_managers
+        .iter()
+        .map(|m| {
+            let field_ident = format_ident!("init_{}_state", m.variant.to_lowercase());
+            let module_ident = format_ident!("{}", m.module_name);
+            let manager_type = format_ident!("{}", m.manager_type);
+            quote! {
+                #field_ident: Option<Box<dyn FnOnce() -> <::#module_ident::#manager_type as saucer_core::EffectManager>::State<AppMsg> + Send>>,
+            }
+        })
+        .collect();
+    let state_init_defaults: Vec<_> = effect_managers
+        .iter()
+        .map(|m| {
+            let field_ident = format_ident!("init_{}_state", m.variant.to_lowercase());
+            quote! { #field_ident: None, }
+        })
+        .collect();
+    let state_init_methods: Vec<_> = effect_managers
+        .iter()
+        .map(|m| {
+            let field_ident = format_ident!("init_{}_state", m.variant.to_lowercase());
+            let module_ident = format_ident!("{}", m.module_name);
+            let manager_type = format_ident!("{}", m.manager_type);
+            let doc = format!(
+                " Create `{}::{}`'s state with `init` instead of `EffectManager::init`.",
+                m.module_name, m.manager_type
+            );
+            quote! {
+                #[doc = #doc]
+                #[allow(dead_code)]
+                pub fn #field_ident(
+                    &mut self,
+                    init: impl FnOnce() -> <::#module_ident::#manager_type as saucer_core::EffectManager>::State<AppMsg> + Send + 'static,
+                ) {
+                    self.#field_ident = Some(Box::new(init));
+                }
+            }
+        })
+        .collect();
+
+    let manager_init: Vec<_> = effect_managers
+        .iter()
+        .map(|m| {
+            let manager_ident = format_ident!("{}_manager", m.variant.to_lowercase());
+            let state_ident = format_ident!("{}_state", m.variant.to_lowercase());
+            let init_field_ident = format_ident!("init_{}_state", m.variant.to_lowercase());
+            let module_ident = format_ident!("{}", m.module_name);
+            let manager_type = format_ident!("{}", m.manager_type);
+            let manager_path = quote! { ::#module_ident::#manager_type };
+            if !m.spawn {
+                return quote! {
+                    let #manager_ident = #manager_path;
+                    let mut #state_ident = match self.#init_field_ident.take() {
+                        Some(init) => init(),
+                        None => <#manager_path as saucer_core::EffectManager>::init::<AppMsg>(),
+                    };
+                };
+            }
+
+            let input_ident = format_ident!("{}_input", m.variant.to_lowercase());
+            let router_ident = format_ident!("{}_router", m.variant.to_lowercase());
+            let (sub_ty, on_subscriptions) = if m.subscription_type.is_some() {
+                (
+                    quote! { <#manager_path as saucer_core::SubscriptionManager>::Subscription<AppMsg> },
+                    quote! {
+                        saucer_core::SubscriptionManager::on_subscriptions(&manager, &#router_ident, state, subs)
+                    },
+                )
+            } else {
+                (
+                    quote! { std::convert::Infallible },
+                    quote! { { let _ = subs; state } },
+                )
+            };
+            quote! {
+                let #input_ident = {
+                    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<
+                        saucer_core::ManagerInput<
+                            <#manager_path as saucer_core::EffectManager>::Request<AppMsg>,
+                            <#manager_path as saucer_core::EffectManager>::SelfMsg,
+                            #sub_ty,
+                        >,
+                    >();
+                    let init_state = self.#init_field_ident.take();
+                    tokio::task::spawn_blocking(move || {
+                        let manager = #manager_path;
+                        let mut state = match init_state {
+                            Some(init) => init(),
+                            None => <#manager_path as saucer_core::EffectManager>::init::<AppMsg>(),
+                        };
+                        while let Some(input) = rx.blocking_recv() {
+                            state = match input {
+                                saucer_core::ManagerInput::Effects(batch) => {
+                                    saucer_core::EffectManager::on_effects(&manager, &#router_ident, state, batch)
+                                }
+                                saucer_core::ManagerInput::SelfMsg(msg) => {
+                                    saucer_core::EffectManager::on_self_msg(&manager, &#router_ident, state, msg)
+                                }
+                                saucer_core::ManagerInput::Subscriptions(subs) => #on_subscriptions,
+                            };
+                        }
+                    });
+                    tx
+                };
+            }
+        })
+        .collect();
+
+    // Requests from one `Cmd` are grouped per manager (keeping their order) so
+    // each manager sees the whole batch in a single `on_effects` call.
+    let batch_idents: Vec<_> = effect_managers
+        .iter()
+        .map(|m| format_ident!("{}_batch", m.variant.to_lowercase()))
+        .collect();
+
+    let request_grouping: Vec<_> = effect_managers
+        .iter()
+        .zip(&batch_idents)
+        .map(|(m, batch_ident)| {
+            let variant_ident = format_ident!("{}", m.variant);
+            quote! {
+                Request::#variant_ident(r) => #batch_ident.push(r),
+            }
+        })
+        .collect();
+
+    let request_dispatch: Vec<_> = effect_managers
+        .iter()
+        .zip(&batch_idents)
+        .map(|(m, batch_ident)| {
+            if m.spawn {
+                let input_ident = format_ident!("{}_input", m.variant.to_lowercase());
+                return quote! {
+                    if !#batch_ident.is_empty() {
+                        let _ = #input_ident.send(saucer_core::ManagerInput::Effects(#batch_ident));
+                    };
+                };
+            }
+            let router_ident = format_ident!("{}_router", m.variant.to_lowercase());
+            let manager_ident = format_ident!("{}_manager", m.variant.to_lowercase());
+            let state_ident = format_ident!("{}_state", m.variant.to_lowercase());
+            quote! {
+                #state_ident = if #batch_ident.is_empty() {
+                    #state_ident
+                } else {
+                    saucer_core::EffectManager::on_effects(
+                        &#manager_ident,
+                        &#router_ident,
+                        #state_ident,
+                        #batch_ident,
+                    )
+                };
+            }
+        })
+        .collect();
+
+    let sub_managers: Vec<&ManagerInfo> = effect_managers
+        .iter()
+        .filter(|m| m.subscription_type.is_some())
+        .collect();
+
+    // Re-evaluate subscriptions against the current model and hand each manager its full set.
+    let subscription_dispatch = if sub_managers.is_empty() {
+        quote! {}
+    } else {
+        let subs_idents: Vec<_> = sub_managers
+            .iter()
+            .map(|m| format_ident!("{}_subs", m.variant.to_lowercase()))
+            .collect();
+        let variant_idents: Vec<_> = sub_managers
+            .iter()
+            .map(|m| format_ident!("{}", m.variant))
+            .collect();
+        let deliveries: Vec<_> = sub_managers
+            .iter()
+            .zip(&subs_idents)
+            .map(|(m, subs_ident)| {
+                if m.spawn {
+                    let input_ident = format_ident!("{}_input", m.variant.to_lowercase());
+                    return quote! {
+                        let _ = #input_ident.send(saucer_core::ManagerInput::Subscriptions(#subs_ident));
+                    };
+                }
+                let router_ident = format_ident!("{}_router", m.variant.to_lowercase());
+                let manager_ident = format_ident!("{}_manager", m.variant.to_lowercase());
+                let state_ident = format_ident!("{}_state", m.variant.to_lowercase());
+                quote! {
+                    #state_ident = saucer_core::SubscriptionManager::on_subscriptions(
+                        &#manager_ident,
+                        &#router_ident,
+                        #state_ident,
+                        #subs_ident,
+                    );
+                }
+            })
+            .collect();
+        quote! {
+            {
+                #(let mut #subs_idents = Vec::new();)*
+                for sub in (self.subscriptions)(&model).into_inner() {
+                    match sub {
+                        #(Subscription::#variant_idents(s) => #subs_idents.push(s),)*
+                    }
+                }
+                #(#deliveries)*
+            }
+        }
+    };
+
+    let subscriptions_field_attr = if sub_managers.is_empty() {
+        quote! { #[allow(dead_code)] } // no manager consumes subscriptions
+    } else {
+        quote! {}
+    };
+
+    let self_msg_dispatch: Vec<_> = effect_managers
+        .iter()
+        .filter(|m| m.self_msg_type != "()")
+        .map(|m| {
+            let variant_ident = format_ident!("{}", m.variant);
+            let manager_label = m.variant.clone();
+            let handle = if m.spawn {
+                let input_ident = format_ident!("{}_input", m.variant.to_lowercase());
+                quote! {
+                    let _ = #input_ident.send(saucer_core::ManagerInput::SelfMsg(msg));
+                }
+            } else {
+                let router_ident = format_ident!("{}_router", m.variant.to_lowercase());
+                let manager_ident = format_ident!("{}_manager", m.variant.to_lowercase());
+                let state_ident = format_ident!("{}_state", m.variant.to_lowercase());
+                quote! {
+                    #state_ident = saucer_core::EffectManager::on_self_msg(
+                        &#manager_ident,
+                        &#router_ident,
+                        #state_ident,
+                        msg,
+                    );
+                }
+            };
+            quote! {
+                SelfMsg::#variant_ident(msg) => {
+                    let observation = Observation::ManagerMsg {
+                        ts: std::time::SystemTime::now(),
+                        manager: #manager_label,
+                        data: SelfMsg::#variant_ident(msg.clone()),
+                    };
+                    observer(&observation);
+                    #handle
+                }
+            }
+        })
+        .collect();
+
+    let self_msg_dispatch_arm = if self_msg_dispatch.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            Some(self_msg) = self.self_rx.recv() => {
+                match self_msg {
+                    #(#self_msg_dispatch)*
+                }
+            }
+        }
+    };
+
+    let ports_match_arms: Vec<_> = ports_with_paths
+        .iter()
+        .filter_map(|(p, _, _)| match p {
+            PortSpec::Outgoing(p) => Some(p),
+            _ => None,
+        })
+        .map(|p| {
+            let variant_ident = format_ident!("{}", ports::to_camel(&p.name));
+            let field_ident = format_ident!("{}", p.name);
+            quote! { PortsRequest::#variant_ident { value } => self.ports.#field_ident.dispatch(value), }
+        })
+        .collect();
+
+    let outgoing_receiver_fields_struct: Vec<_> = ports_with_paths
+        .iter()
+        .filter_map(|(p, _, _)| match p {
+            PortSpec::Outgoing(p) => Some(p),
+            _ => None,
+        })
+        .map(|p| {
+            let field_ident = format_ident!("{}_rx", p.name);
+            let ty: TokenStream = ports::payload_type_tokens(&PortSpec::Outgoing(p.clone()));
+            quote! { #field_ident: tokio::sync::mpsc::UnboundedReceiver<#ty>, }
+        })
+        .collect();
+
+    let outgoing_receiver_init_fields: Vec<_> = ports_with_paths
+        .iter()
+        .filter_map(|(p, _, _)| match p {
+            PortSpec::Outgoing(p) => Some(p),
+            _ => None,
+        })
+        .map(|p| {
+            let field_ident = format_ident!("{}_rx", p.name);
+            quote! { #field_ident: receivers.#field_ident, }
+        })
+        .collect();
+
+    let outgoing_select_arms: Vec<_> = ports_with_paths
+        .iter()
+        .filter_map(|(p, _, _)| match p {
+            PortSpec::Outgoing(p) => Some(p),
+            _ => None,
+        })
+        .map(|p| {
+            let rx_ident = format_ident!("{}_rx", p.name);
+            let port_ident = format_ident!("{}", p.name);
+            quote! {
+                Some(value) = self.#rx_ident.recv() => {
+                    self.ports.#port_ident.deliver(value);
+                }
+            }
+        })
+        .collect();
+
+    let drain_ports_stmts: Vec<_> = ports_with_paths
+        .iter()
+        .filter_map(|(p, _, _)| match p {
+            PortSpec::Outgoing(p) => Some(p),
+            _ => None,
+        })
+        .map(|p| {
+            let rx_ident = format_ident!("{}_rx", p.name);
+            let port_ident = format_ident!("{}", p.name);
+            quote! {
+                while let Ok(v) = self.#rx_ident.try_recv() {
+                    self.ports.#port_ident.deliver(v);
+                }
+            }
+        })
+        .collect();
+
+    let receivers_unused_stmt = if has_outgoing_ports {
+        quote! {}
+    } else {
+        quote! { let _ = &receivers; } // silence unused variable when no outgoing ports
+    };
+
+    let ports_dispatch_arm = if has_outgoing_ports {
+        quote! {
+            Request::Ports(req, _) => {
+                match req {
+                    #(#ports_match_arms)*
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let app_msg_from_bound = if has_ports {
+        quote! { AppMsg: From<#msg_ty>, }
+    } else {
+        quote! {}
+    };
+
+    let ports_struct_field = if has_ports {
+        quote! { ports: Ports<SelfMsg, AppMsg>, }
+    } else {
+        quote! {}
+    };
+
+    let ports_init_field = if has_ports {
+        quote! { ports, }
+    } else {
+        quote! {}
+    };
+
+    let ports_method = if has_ports {
+        quote! { pub fn ports(&self) -> Ports<SelfMsg, AppMsg> { self.ports.clone() } }
+    } else {
+        quote! {}
+    };
+
+    let ports_setup_stmts = if has_ports {
+        quote! {
+            let router = saucer_core::Router::new(app_tx.clone(), self_tx.clone());
+            let (ports, receivers) = Ports::new(router.clone());
+            #receivers_unused_stmt
+        }
+    } else {
+        quote! {}
+    };
+
+    let reconciler_module = format_ident!("{}", reconciler_manager.module_name);
+    let reconciler_type = format_ident!("{}", reconciler_manager.manager_type);
+    let reconciler_variant = format_ident!("{}", reconciler_manager.variant);
+    let reconciler_path = quote! { ::#reconciler_module::#reconciler_type };
+    // The reconciler's sender wraps its self-messages the way its router
+    // does. A `()` reconciler has no `SelfMsg` variant, so its sender is typed
+    // `SendToManager<_, ()>` rather than `<_, SelfMsg>` (which only compiled
+    // while no manager had self-messages) and sends on its manager's own
+    // channel; saucer-core's `CoreManager` has none and ignores self-messages
+    // anyway.
+    let (reconciler_wire, reconciler_sender) = if reconciler_manager.self_msg_type == "()" {
+        let channel = if effect_managers
+            .iter()
+            .any(|m| m.module_name == reconciler_manager.module_name)
+        {
+            let router_ident =
+                format_ident!("{}_router", reconciler_manager.variant.to_lowercase());
+            quote! { #router_ident.self_sender() }
+        } else {
+            quote! { tokio::sync::mpsc::unbounded_channel().0 }
+        };
+        (
+            quote! { () },
+            quote! {
+                saucer_core::SendToManager::<#reconciler_path, ()>::new(#channel, |msg| msg)
+            },
+        )
+    } else {
+        (
+            quote! { SelfMsg },
+            quote! {
+                saucer_core::SendToManager::<#reconciler_path, SelfMsg>::new(
+                    self.self_tx.clone(),
+                    |msg| SelfMsg::#reconciler_variant(msg),
+                )
+            },
+        )
+    };
+
+    quote! {
+        type GeneratedAppMsg = #msg_ty;
+
+        pub struct Runtime<Init, Update, Subs, ViewFn, Recon, Model, ViewOut, AppMsg = GeneratedAppMsg>
+        where
+            AppMsg: Clone + Send + 'static,
+            #app_msg_from_bound
+            SelfMsg: Clone + Send + 'static,
+            Model: Send + 'static,
+        {
+            init: Option<Init>,
+            update: Update,
+            #subscriptions_field_attr
+            subscriptions: Subs,
+            view: ViewFn,
+            reconciler: Recon,
+            observer: ObserverFn<AppMsg, Request<AppMsg>, SelfMsg>,
+            #[allow(dead_code)] // ports-only runtimes store this even when no managers are present
+            app_tx: tokio::sync::mpsc::UnboundedSender<AppMsg>,
+            #ports_struct_field
+            app_rx: tokio::sync::mpsc::UnboundedReceiver<AppMsg>,
+            #[allow(dead_code)] // unread when neither a manager nor the reconciler has a `SelfMsg` variant
+            self_tx: tokio::sync::mpsc::UnboundedSender<SelfMsg>,
+            #[allow(dead_code)]
+            self_rx: tokio::sync::mpsc::UnboundedReceiver<SelfMsg>,
+            req_tx: tokio::sync::mpsc::UnboundedSender<Vec<Request<AppMsg>>>,
+            req_rx: tokio::sync::mpsc::UnboundedReceiver<Vec<Request<AppMsg>>>,
+            #(#outgoing_receiver_fields_struct)*
+            _model: std::marker::PhantomData<Model>,
+            _view: std::marker::PhantomData<ViewOut>,
+            debugger: Option<DebuggerState<Model, AppMsg>>,
+            debug_tx: tokio::sync::mpsc::UnboundedSender<DebugCommand>,
+            debug_rx: tokio::sync::mpsc::UnboundedReceiver<DebugCommand>,
+            #(#state_init_fields)*
+        }
+
+        impl<Init, Update, Subs, ViewFn, Recon, Model, ViewOut, AppMsg> Runtime<Init, Update, Subs, ViewFn, Recon, Model, ViewOut, AppMsg>
+        where
+            Init: FnOnce() -> (Model, Cmd<AppMsg>),
+            Update: Fn(Model, AppMsg) -> (Model, Cmd<AppMsg>),
+            Subs: Fn(&Model) -> Sub<AppMsg>,
+            ViewFn: Fn(&Model) -> ViewOut,
+            Recon: FnMut(&ViewOut, &saucer_core::SendToManager<#reconciler_path, #reconciler_wire>),
+            Model: Send + 'static,
+            SelfMsg: Clone + Send + 'static,
+            AppMsg: Clone + Send + 'static,
+            #app_msg_from_bound
+        {
+            fn drain_port_queues(&mut self) {
+                #(#drain_ports_stmts)*
+            }
+
+            pub fn new(
+                init: Init,
+                update: Update,
+                subscriptions: Subs,
+                view: ViewFn,
+                reconciler: Recon,
+                observer: ObserverFn<AppMsg, Request<AppMsg>, SelfMsg>,
+            ) -> Self {
+                let (app_tx, app_rx) = tokio::sync::mpsc::unbounded_channel();
+                let (self_tx, self_rx) = tokio::sync::mpsc::unbounded_channel();
+                let (req_tx, req_rx) = tokio::sync::mpsc::unbounded_channel();
+                let (debug_tx, debug_rx) = tokio::sync::mpsc::unbounded_channel();
+                #ports_setup_stmts
+
+                Self {
+                    init: Some(init),
+                    update,
+                    subscriptions,
+                    view,
+                    reconciler,
+                    observer,
+                    app_tx,
+                    #ports_init_field
+                    app_rx,
+                    self_tx,
+                    self_rx,
+                    req_tx,
+                    req_rx,
+                    #(#outgoing_receiver_init_fields)*
+                    _model: std::marker::PhantomData,
+                    _view: std::marker::PhantomData,
+                    debugger: None,
+                    debug_tx,
+                    debug_rx,
+                    #(#state_init_defaults)*
+                }
+            }
+
+            #ports_method
+
+            #(#state_init_methods)*
+
+            /// Keep the last `capacity` `(Msg, Model)` snapshots, starting with
+            /// `init`'s model, and return a handle for stepping back through them.
+            pub fn debugger(&mut self, capacity: usize) -> Debugger<Model, AppMsg>
+            where
+                Model: Clone,
+            {
+                assert!(capacity > 0, "debugger history needs room for at least one model");
+                let history = std::sync::Arc::new(std::sync::Mutex::new(std::collections::VecDeque::new()));
+                self.debugger = Some(DebuggerState {
+                    history: history.clone(),
+                    capacity,
+                    snapshot: <Model as Clone>::clone,
+                });
+                Debugger {
+                    history,
+                    commands: self.debug_tx.clone(),
+                }
+            }
+
+            fn enqueue_cmd(
+                tx: &tokio::sync::mpsc::UnboundedSender<Vec<Request<AppMsg>>>,
+                cmd: Cmd<AppMsg>,
+            ) {
+                let batch = cmd.into_inner();
+                if !batch.is_empty() {
+                    let _ = tx.send(batch);
+                }
+            }
+
+            pub async fn run(mut self) {
+                let (mut model, init_cmd) = self
+                    .init
+                    .take()
+                        .expect("Runtime::run called more than once")();
+
+                let observer = self.observer.clone();
+                Self::enqueue_cmd(&self.req_tx, init_cmd);
+
+                #(#router_decls)*
+                let sender = #reconciler_sender;
+                #(#manager_init)*
+                #subscription_dispatch
+
+                let mut view_cache = (self.view)(&model);
+                // While the debugger shows a past model, app messages wait in their queue.
+                let mut paused = false;
+                if let Some(debugger) = &self.debugger {
+                    debugger.record(None, &model);
+                }
+                (self.reconciler)(&view_cache, &sender);
+
+                loop {
+                    tokio::select! {
+                        Some(batch) = self.req_rx.recv() => {
+                            #(let mut #batch_idents = Vec::new();)*
+                            let mut shutdown = false;
+                            for req in batch {
+                                let observation = Observation::Effect { ts: std::time::SystemTime::now(), data: req.clone() };
+                                observer(&observation);
+                                match req {
+                                    // Requests after a shutdown are dropped; earlier ones still go out.
+                                    Request::Core(saucer_core::CoreRequest::Shutdown) => { shutdown = true; break; },
+                                    #(#request_grouping)*
+                                    #ports_dispatch_arm
+                                }
+                            }
+                            #(#request_dispatch)*
+                            if shutdown {
+                                self.drain_port_queues();
+                                break;
+                            }
+                        }
+                        Some(app_evt) = self.app_rx.recv(), if !paused => {
+                            let observation = Observation::Event { ts: std::time::SystemTime::now(), data: app_evt.clone() };
+                            observer(&observation);
+                            let recorded_msg = self.debugger.as_ref().map(|_| app_evt.clone());
+                            let (new_model, cmd) = (self.update)(model, app_evt);
+                            model = new_model;
+                            if let Some(debugger) = &self.debugger {
+                                debugger.record(recorded_msg, &model);
+                            }
+                            Self::enqueue_cmd(&self.req_tx, cmd);
+                            #subscription_dispatch
+                            view_cache = (self.view)(&model);
+                            (self.reconciler)(&view_cache, &sender);
+                        }
+                        Some(command) = self.debug_rx.recv() => {
+                            match command {
+                                DebugCommand::JumpTo(index) => {
+                                    let past_view = self.debugger.as_ref().and_then(|debugger| {
+                                        let history = debugger.history.lock().expect("debugger history lock");
+                                        history.get(index).map(|entry| (self.view)(&entry.model))
+                                    });
+                                    if let Some(past_view) = past_view {
+                                        paused = true;
+                                        view_cache = past_view;
+                                        (self.reconciler)(&view_cache, &sender);
+                                    }
+                                }
+                                DebugCommand::Resume => {
+                                    if paused {
+                                        paused = false;
+                                        view_cache = (self.view)(&model);
+                                        (self.reconciler)(&view_cache, &sender);
+                                    }
+                                }
+                            }
+                        }
+                        #self_msg_dispatch_arm
+                        #(#self_msg_forward_arms)*
+                        #(#outgoing_select_arms)*
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The time-travel debugger's handle and the history `Runtime` records into
+/// once `Runtime::debugger` is called.
+fn generate_debugger() -> TokenStream {
+    quote! {
+        /// One step of the debugger's history: the message `update` ran
+        /// (`None` for `init`) and the model it produced.
+        #[derive(Clone, Debug)]
+        pub struct DebugEntry<Model, AppMsg = GeneratedAppMsg> {
+            pub msg: Option<AppMsg>,
+            pub model: Model,
+        }
+
+        enum DebugCommand {
+            JumpTo(usize),
+            Resume,
+        }
+
+        struct DebuggerState<Model, AppMsg> {
+            history: std::sync::Arc<std::sync::Mutex<std::collections::VecDeque<DebugEntry<Model, AppMsg>>>>,
+            capacity: usize,
+            snapshot: fn(&Model) -> Model,
+        }
+
+        impl<Model, AppMsg> DebuggerState<Model, AppMsg> {
+            fn record(&self, msg: Option<AppMsg>, model: &Model) {
+                let mut history = self.history.lock().expect("debugger history lock");
+                if history.len() == self.capacity {
+                    history.pop_front();
+                }
+                history.push_back(DebugEntry { msg, model: (self.snapshot)(model) });
+            }
+        }
+
+        /// Handle to a running `Runtime`'s model history.
+        ///
+        /// `jump_to` re-runs `view` and the reconciler on a past model and
+        /// pauses the app: messages queue up until `resume` restores the
+        /// live model and lets them through.
+        pub struct Debugger<Model, AppMsg = GeneratedAppMsg> {
+            history: std::sync::Arc<std::sync::Mutex<std::collections::VecDeque<DebugEntry<Model, AppMsg>>>>,
+            commands: tokio::sync::mpsc::UnboundedSender<DebugCommand>,
+        }
+
+        impl<Model, AppMsg> Clone for Debugger<Model, AppMsg> {
+            fn clone(&self) -> Self {
+                Self {
+                    history: self.history.clone(),
+                    commands: self.commands.clone(),
+                }
+            }
+        }
+
+        impl<Model: Clone, AppMsg: Clone> Debugger<Model, AppMsg> {
+            /// The recorded steps, oldest first. Once the history is full the
+            /// oldest step is dropped, so indices shift while the app runs;
+            /// they hold still while it is paused.
+            pub fn history(&self) -> Vec<DebugEntry<Model, AppMsg>> {
+                self.history
+                    .lock()
+                    .expect("debugger history lock")
+                    .iter()
+                    .cloned()
+                    .collect()
+            }
+
+            /// Show the model at `index` in `history`. Out-of-range indices are ignored.
+            pub fn jump_to(&self, index: usize) {
+                let _ = self.commands.send(DebugCommand::JumpTo(index));
+            }
+
+            /// Return to the live model and process queued messages again.
+            pub fn resume(&self) {
+                let _ = self.commands.send(DebugCommand::Resume);
+            }
+        }
+    }
+}
+
+/// `TestRuntime`: drives `init`/`update` by hand and keeps emitted requests
+/// for the test to inspect and resolve, instead of handing them to managers.
+fn generate_test_runtime_struct() -> TokenStream {
+    quote! {
+        /// Runs an app without managers, timers or an async executor.
+        ///
+        /// Messages queued with `send` are only applied by `step`. Requests
+        /// emitted by `init` and `update` wait in `pending_requests` until the
+        /// test `resolve`s them with the result their manager would produce.
+        pub struct TestRuntime<Model, Update, AppMsg = GeneratedAppMsg> {
+            model: Option<Model>,
+            update: Update,
+            queue: std::collections::VecDeque<AppMsg>,
+            pending: Vec<Request<AppMsg>>,
+            shut_down: bool,
+        }
+
+        impl<Model, Update, AppMsg> TestRuntime<Model, Update, AppMsg>
+        where
+            Update: Fn(Model, AppMsg) -> (Model, Cmd<AppMsg>),
+            AppMsg: Clone + 'static,
+        {
+            pub fn new(init: impl FnOnce() -> (Model, Cmd<AppMsg>), update: Update) -> Self {
+                let (model, cmd) = init();
+                let mut runtime = Self {
+                    model: Some(model),
+                    update,
+                    queue: std::collections::VecDeque::new(),
+                    pending: Vec::new(),
+                    shut_down: false,
+                };
+                runtime.collect(cmd);
+                runtime
+            }
+
+            fn collect(&mut self, cmd: Cmd<AppMsg>) {
+                for req in cmd.into_inner() {
+                    match req {
+                        // Requests after a shutdown are dropped, as in `Runtime`.
+                        Request::Core(saucer_core::CoreRequest::Shutdown) => {
+                            self.shut_down = true;
+                            break;
+                        }
+                        req => self.pending.push(req),
+                    }
+                }
+            }
+
+            /// Queue `msg` for the next `step`.
+            pub fn send(&mut self, msg: AppMsg) {
+                self.queue.push_back(msg);
+            }
+
+            /// Apply the oldest queued message. Returns `false` once the queue
+            /// is empty or the app has shut down.
+            pub fn step(&mut self) -> bool {
+                if self.shut_down {
+                    return false;
+                }
+                let Some(msg) = self.queue.pop_front() else {
+                    return false;
+                };
+                let model = self.model.take().expect("model is restored after every update");
+                let (model, cmd) = (self.update)(model, msg);
+                self.model = Some(model);
+                self.collect(cmd);
+                true
+            }
+
+            /// Requests emitted so far and not yet resolved, oldest first.
+            pub fn pending_requests(&self) -> Vec<Request<AppMsg>> {
+                self.pending.clone()
+            }
+
+            /// Complete the request at `request` in `pending_requests` by
+            /// calling its `returns` callback with `result`, and queue the
+            /// message that comes back. `R` is the manager's request type, e.g.
+            /// `rt.resolve::<TimeRequest<_>, _>(0, ())`.
+            pub fn resolve<R, I>(&mut self, request: usize, result: I)
+            where
+                Request<AppMsg>: saucer_core::AsRequest<R>,
+                R: saucer_core::Resolve<I, AppMsg>,
+            {
+                self.check_pending(request);
+                let req = saucer_core::AsRequest::<R>::as_request(&self.pending[request])
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "pending request {} is not a {}",
+                            request,
+                            std::any::type_name::<R>()
+                        )
+                    });
+                let msg = req.resolve(result).unwrap_or_else(|| {
+                    panic!(
+                        "pending request {} does not take a {}",
+                        request,
+                        std::any::type_name::<I>()
+                    )
+                });
+                self.pending.remove(request);
+                self.queue.push_back(msg);
+            }
+
+            /// Complete the request at `request` in `pending_requests` without
+            /// calling its callback, queueing `msg` in its place.
+            pub fn complete_with_msg(&mut self, request: usize, msg: AppMsg) {
+                self.check_pending(request);
+                self.pending.remove(request);
+                self.queue.push_back(msg);
+            }
+
+            fn check_pending(&self, request: usize) {
+                assert!(
+                    request < self.pending.len(),
+                    "no pending request at index {} ({} pending)",
+                    request,
+                    self.pending.len()
+                );
+            }
+
+            pub fn model(&self) -> &Model {
+                self.model.as_ref().expect("model is restored after every update")
+            }
+
+            /// Whether a `shutdown` command has been emitted.
+            pub fn is_shut_down(&self) -> bool {
+                self.shut_down
+            }
+        }
+    }
+}
+
+/// Where a template's transformed source is written, relative to `OUT_DIR`.
+///
+/// Keeps the template's file name; since the transform leaves lines in
+/// place, compiler errors name the same file and line the developer edited.
+pub fn template_include_path(templates_dir: &str, template: &TemplateInfo) -> String {
+    format!(
+        "{}/{}/{}.tea.rs",
+        templates_dir,
+        crate_to_module_name(&template.crate_name),
+        template.module_name
+    )
+}
+
+fn generate_template_module_with_source(
+    template: &TemplateInfo,
+    templates_dir: &str,
+    transformed_source: String,
+    outgoing_ports: &[PortSpec],
+    editor_shadows: &TokenStream,
+) -> TokenStream {
+    let module_ident = format_ident!("{}", template.module_name);
+    let include_path = format!("/{}", template_include_path(templates_dir, template));
+    let original_path = template.path.display().to_string();
+
+    let prelude = template_prelude(&transformed_source, outgoing_ports);
+
+    quote! {
+        pub mod #module_ident {
+            #prelude
+            #[cfg(not(rust_analyzer))]
+            include!(concat!(env!("OUT_DIR"), #include_path));
+            #[cfg(rust_analyzer)]
+            include!(#original_path);
+            #editor_shadows
+        }
+    }
+}
+
+/// Imports the transformed template expects from the runtime.
+fn template_prelude(source: &str, outgoing_ports: &[PortSpec]) -> TokenStream {
+    let needs_cmd_ext = source.contains(".map(");
+    let mut prelude_items: Vec<TokenStream> = Vec::new();
+    if needs_cmd_ext {
+        prelude_items.push(quote! { #[allow(unused_imports)] use super::super::{CmdExt, SubExt}; });
+    }
+    if !outgoing_ports.is_empty() {
+        let names: Vec<_> = outgoing_ports
+            .iter()
+            .filter_map(|p| match p {
+                PortSpec::Outgoing(p) => Some(format_ident!("{}", p.name)),
+                _ => None,
+            })
+            .collect();
+        prelude_items
+            .push(quote! { #[allow(unused_imports)] use super::super::{ #( #names ),* }; });
+    }
+    quote! { #(#prelude_items)* }
+}
+
+/// A template module with the transformed source embedded. The original is
+/// still read with `include_str!` so rustc rebuilds when it changes, and an
+/// editor expanding the macro already sees the transformed code, so no
+/// editor shadows are needed.
+fn generate_inline_template_module(
+    template: &TemplateInfo,
+    transformed_source: &str,
+    outgoing_ports: &[PortSpec],
+) -> Result<TokenStream, BuildError> {
+    let module_ident = format_ident!("{}", template.module_name);
+    let original_path = template.path.display().to_string();
+    let prelude = template_prelude(transformed_source, outgoing_ports);
+    let file =
+        syn::parse_file(transformed_source).map_err(|e| BuildError::parse(&template.path, &e))?;
+    let (attrs, items) = (&file.attrs, &file.items);
+
+    Ok(quote! {
+        pub mod #module_ident {
+            #(#attrs)*
+            #prelude
+            const _: &str = include_str!(#original_path);
+            #(#items)*
+        }
+    })
+}
+
+/// `include_str!` of each manager's Cargo.toml and requests.rs, which codegen
+/// reads for metadata and helpers, so rustc expands `runtime!` again when
+/// they change. Build scripts watch the same files with `rerun-if-changed`.
+fn generate_manager_tracking(managers: &[ManagerInfo]) -> TokenStream {
+    let paths: Vec<String> = managers
+        .iter()
+        .flat_map(|m| [m.manifest_path.clone(), m.requests_path()])
+        .filter(|path| path.exists())
+        .map(|path| path.display().to_string())
+        .collect();
+    quote! { #( const _: &str = include_str!(#paths); )* }
+}
+
+/// Modules that make a template's fictional imports resolve when an editor
+/// analyses the template as written.
+///
+/// rust-analyzer sets `cfg(rust_analyzer)`, so it includes the original
+/// `.tea.rs` instead of the transformed copy. These modules shadow the crate
+/// names the template imports from: `saucer_core::Cmd` and each
+/// `<manager>::command` point at the generated types and helpers, and
+/// dependency templates at their modules in the runtime. Everything else
+/// re-exports the real crate, and `#[port]` resolves to an attribute that
+/// mirrors the port rewrite. rustc never compiles them.
+fn generate_editor_shadows(
+    templates: &[TemplateInfo],
+    managers: &[ManagerInfo],
+    used_helpers: &[(String, String)],
+    package_name: &str,
+) -> TokenStream {
+    let uses_helpers_from = |module: &str| used_helpers.iter().any(|(m, _)| m == module);
+    let mut shadows: BTreeMap<String, Vec<TokenStream>> = BTreeMap::new();
+
+    let core_command = if uses_helpers_from("saucer_core") {
+        quote! { pub mod command { pub use super::super::super::super::core::*; } }
+    } else {
+        quote! {}
+    };
+    shadows
+        .entry("saucer_core".to_string())
+        .or_default()
+        .push(quote! {
+            pub use ::saucer_core::*;
+            pub use super::super::super::{Cmd, Sub};
+            #core_command
+        });
+
+    for manager in managers {
+        let module_ident = format_ident!("{}", manager.module_name);
+        let command = if uses_helpers_from(&manager.module_name) {
+            quote! { pub mod command { pub use super::super::super::super::#module_ident::*; } }
+        } else {
+            quote! {}
+        };
+        shadows
+            .entry(manager.module_name.clone())
+            .or_default()
+            .push(quote! {
+                pub use ::#module_ident::*;
+                #command
+            });
+    }
+
+    let own_crate = crate_to_module_name(package_name);
+    let mut template_crates: BTreeMap<String, Vec<proc_macro2::Ident>> = BTreeMap::new();
+    for template in templates {
+        template_crates
+            .entry(crate_to_module_name(&template.crate_name))
+            .or_default()
+            .push(format_ident!("{}", template.module_name));
+    }
+    for (crate_mod, modules) in template_crates {
+        let crate_ident = format_ident!("{}", crate_mod);
+        // A crate cannot name itself, so only other crates get the glob.
+        let reexport = if crate_mod == own_crate {
+            quote! {}
+        } else {
+            quote! { pub use ::#crate_ident::*; }
+        };
+        shadows.entry(crate_mod).or_default().push(quote! {
+            #reexport
+            pub use super::super::super::#crate_ident::{ #( #modules ),* };
+        });
+    }
+
+    let modules = shadows.into_iter().map(|(name, items)| {
+        let ident = format_ident!("{}", name);
+        quote! {
+            #[cfg(rust_analyzer)]
+            #[allow(unused_imports, dead_code)]
+            mod #ident {
+                #( #items )*
+            }
+        }
+    });
+    quote! {
+        #( #modules )*
+        // Globbed so a template's own `use saucer_core::port` still wins.
+        #[cfg(rust_analyzer)]
+        #[allow(unused_imports)]
+        use self::template_attrs::*;
+        #[cfg(rust_analyzer)]
+        #[allow(unused_imports)]
+        mod template_attrs {
+            pub use ::saucer_core::port;
+        }
+    }
+}
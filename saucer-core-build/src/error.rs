@@ -0,0 +1,218 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use quote::ToTokens;
+use syn::spanned::Spanned;
+
+use crate::source_map::SourceMap;
+
+/// A problem found while generating the runtime.
+///
+/// Carries the file and span that caused it so build scripts can report
+/// rustc-style diagnostics instead of panicking with a backtrace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildError {
+    pub message: String,
+    pub path: Option<PathBuf>,
+    pub span: Option<SourceSpan>,
+    pub help: Option<String>,
+}
+
+/// A region of a source file. Lines are 1-based, columns are 0-based
+/// character offsets (the same convention as `proc_macro2::LineColumn`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceSpan {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl SourceSpan {
+    /// Span of a parsed syntax node. Only meaningful for nodes parsed from
+    /// source text, which is all codegen ever reports on.
+    pub fn of(span: proc_macro2::Span) -> Self {
+        let start = span.start();
+        let end = span.end();
+        Self {
+            line: start.line,
+            column: start.column,
+            end_line: end.line,
+            end_column: end.column,
+        }
+    }
+
+    /// Span of the byte range `range` within `source`.
+    pub fn from_byte_range(source: &str, range: std::ops::Range<usize>) -> Self {
+        let (line, column) = line_column(source, range.start);
+        let (end_line, end_column) = line_column(source, range.end.max(range.start));
+        Self {
+            line,
+            column,
+            end_line,
+            end_column,
+        }
+    }
+}
+
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(source.len());
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    (line, before[line_start..].chars().count())
+}
+
+impl BuildError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            path: None,
+            span: None,
+            help: None,
+        }
+    }
+
+    /// Attach the file the problem was found in.
+    pub fn in_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Point at a parsed syntax node within the file.
+    ///
+    /// Inside the `runtime!` macro the node's spans are the call site's, so
+    /// its tokens are looked up in the file's text instead (the first match);
+    /// attach the file with [`in_file`](Self::in_file) first.
+    pub fn at(mut self, node: &impl ToTokens) -> Self {
+        self.span = if proc_macro::is_available() {
+            self.path.as_ref().and_then(|path| {
+                let source = std::fs::read_to_string(path).ok()?;
+                let range = SourceMap::new(&source)?.locate(node)?;
+                Some(SourceSpan::from_byte_range(&source, range))
+            })
+        } else {
+            Some(SourceSpan::of(node.span()))
+        };
+        self
+    }
+
+    /// Point at an explicit region within the file.
+    pub fn at_span(mut self, span: SourceSpan) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// A `syn` parse failure in `path`. Inside the `runtime!` macro the
+    /// error has no position in the file, so only the file is reported.
+    pub fn parse(path: impl AsRef<Path>, err: &syn::Error) -> Self {
+        let error = Self::new(format!("failed to parse: {}", err)).in_file(path);
+        if proc_macro::is_available() {
+            error
+        } else {
+            error.at_span(SourceSpan::of(err.span()))
+        }
+    }
+
+    /// An I/O failure reading `path`.
+    pub fn io(path: impl AsRef<Path>, err: &std::io::Error) -> Self {
+        let path = path.as_ref();
+        Self::new(format!("failed to read {}: {}", path.display(), err)).in_file(path)
+    }
+
+    /// Render as a rustc-style diagnostic, reading the file for the snippet.
+    pub fn render(&self) -> String {
+        let source = self
+            .path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok());
+        self.render_with_source(source.as_deref())
+    }
+
+    /// Render as a rustc-style diagnostic using `source` for the snippet.
+    pub fn render_with_source(&self, source: Option<&str>) -> String {
+        let snippet_line = self.span.zip(source).and_then(|(span, source)| {
+            Some((span, source.lines().nth(span.line.checked_sub(1)?)?))
+        });
+        let gutter = match snippet_line {
+            Some((span, _)) => span.line.to_string().len(),
+            None => 1,
+        };
+        let pad = " ".repeat(gutter);
+
+        let mut out = format!("error: {}", self.message);
+        if let Some(path) = &self.path {
+            match self.span {
+                Some(span) => out.push_str(&format!(
+                    "\n{}--> {}:{}:{}",
+                    pad,
+                    path.display(),
+                    span.line,
+                    span.column + 1
+                )),
+                None => out.push_str(&format!("\n{}--> {}", pad, path.display())),
+            }
+        }
+
+        if let Some((span, text)) = snippet_line {
+            let width = text.chars().count();
+            let start = span.column.min(width);
+            let end = if span.end_line == span.line {
+                span.end_column.clamp(start + 1, width.max(start + 1))
+            } else {
+                width.max(start + 1)
+            };
+            out.push_str(&format!("\n{} |", pad));
+            out.push_str(&format!("\n{} | {}", span.line, text));
+            out.push_str(&format!(
+                "\n{} | {}{}",
+                pad,
+                " ".repeat(start),
+                "^".repeat(end - start)
+            ));
+        }
+
+        if let Some(help) = &self.help {
+            out.push_str(&format!("\n{} |\n{} = help: {}", pad, pad, help));
+        }
+        out
+    }
+
+    /// Report to cargo from a build script: the headline as a `cargo::error`
+    /// (which fails the build) and the full diagnostic on stderr.
+    pub fn emit(&self) {
+        let location = match (&self.path, self.span) {
+            (Some(path), Some(span)) => {
+                format!(" ({}:{}:{})", path.display(), span.line, span.column + 1)
+            }
+            (Some(path), None) => format!(" ({})", path.display()),
+            _ => String::new(),
+        };
+        println!(
+            "cargo::error={}{}",
+            self.message.replace('\n', " "),
+            location
+        );
+        eprintln!("{}\n", self.render());
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+        if let Some(path) = &self.path {
+            write!(f, " at {}", path.display())?;
+            if let Some(span) = self.span {
+                write!(f, ":{}:{}", span.line, span.column + 1)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for BuildError {}
@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use toml::Value;
+
+use crate::error::{BuildError, SourceSpan};
+use crate::metadata;
+
+#[derive(Debug, Clone)]
+pub struct DepInfo {
+    pub crate_name: String,
+    pub module_name: String,
+    pub path: std::path::PathBuf,
+    pub has_templates: bool,
+    pub excludes_templates: bool,
+}
+
+pub fn crate_to_module_name(crate_name: &str) -> String {
+    crate_name.replace('-', "_")
+}
+
+/// Read and parse a Cargo.toml, pointing at the offending TOML on failure.
+pub fn read_manifest(cargo_toml_path: &Path) -> Result<Value, BuildError> {
+    let content = std::fs::read_to_string(cargo_toml_path)
+        .map_err(|e| BuildError::io(cargo_toml_path, &e))?;
+    content.parse().map_err(|e: toml::de::Error| {
+        let err = BuildError::new(format!("failed to parse Cargo.toml: {}", e.message()))
+            .in_file(cargo_toml_path);
+        match e.span() {
+            Some(range) => err.at_span(SourceSpan::from_byte_range(&content, range)),
+            None => err,
+        }
+    })
+}
+
+pub fn read_crate_metadata(cargo_toml_path: &Path) -> Result<bool, BuildError> {
+    let toml = read_manifest(cargo_toml_path)?;
+    Ok(toml
+        .get("package")
+        .and_then(|p| p.get("metadata"))
+        .and_then(|m| m.get("saucer"))
+        .and_then(|v| v.get("has_templates"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false))
+}
+
+pub fn package_exclude_has_tea_glob(cargo_toml_path: &Path) -> Result<bool, BuildError> {
+    let toml = read_manifest(cargo_toml_path)?;
+    let excludes = toml
+        .get("package")
+        .and_then(|p| p.get("exclude"))
+        .and_then(|e| e.as_array())
+        .cloned()
+        .unwrap_or_default();
+    Ok(excludes
+        .into_iter()
+        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+        .any(|s| s.contains(".tea.rs")))
+}
+
+pub fn dependency_infos(cargo_toml_path: &Path) -> Result<Vec<DepInfo>, BuildError> {
+    metadata::direct_dependencies(cargo_toml_path)?
+        .into_iter()
+        .map(|dep| {
+            Ok(DepInfo {
+                has_templates: read_crate_metadata(&dep.manifest_path)?,
+                excludes_templates: package_exclude_has_tea_glob(&dep.manifest_path)?,
+                path: dep.dir().to_path_buf(),
+                crate_name: dep.name,
+                module_name: dep.module_name,
+            })
+        })
+        .collect()
+}
+
+/// Check that the local crate and every template-providing dependency
+/// package their `.tea.rs` files. Cargo never compiles templates on its own
+/// (`mod` only resolves `.rs` names, and codegen includes transformed copies
+/// from `OUT_DIR`), but a crate that excludes them builds from a path and
+/// breaks once published. Problems are pushed to `errors`.
+pub fn run_hygiene_checks(cargo_toml_path: &Path, errors: &mut Vec<BuildError>) {
+    match package_exclude_has_tea_glob(cargo_toml_path) {
+        Ok(false) => {}
+        Ok(true) => errors.push(
+            BuildError::new("crate excludes its templates from its package")
+                .in_file(cargo_toml_path)
+                .with_help("remove the `.tea.rs` glob from `exclude` in [package]; codegen reads templates when the packaged crate builds"),
+        ),
+        Err(e) => errors.push(e),
+    }
+
+    let deps = match dependency_infos(cargo_toml_path) {
+        Ok(deps) => deps,
+        Err(e) => {
+            errors.push(e);
+            return;
+        }
+    };
+    for dep in deps {
+        if dep.has_templates && dep.excludes_templates {
+            errors.push(
+                BuildError::new(format!(
+                    "dependency `{}` excludes its templates from its package",
+                    dep.crate_name
+                ))
+                .in_file(dep.path.join("Cargo.toml"))
+                .with_help("remove the `.tea.rs` glob from `exclude` in its [package]; apps read its templates from the published crate"),
+            );
+        }
+    }
+}
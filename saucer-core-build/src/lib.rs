@@ -0,0 +1,370 @@
+//! Build-time code generation for runtime.rs
+//!
+//! This crate provides utilities for build.rs scripts to generate
+//! the unified runtime module with Request enum, Cmd type, command helpers,
+//! and Runtime struct. Build scripts reach it as `saucer_core::build` with
+//! saucer-core's `build` feature; the `runtime!` macro uses it directly.
+
+// Only to tell whether codegen runs inside the `runtime!` macro.
+extern crate proc_macro;
+
+mod ast;
+mod builder;
+mod emit;
+mod error;
+mod hygiene;
+mod managers;
+mod metadata;
+mod ports;
+mod request;
+mod source_map;
+mod templates;
+mod transform;
+
+use crate::ast::{ManagerInfo, PortSpec, RuntimeSpec};
+use crate::builder::RuntimeConfig;
+use crate::hygiene::{crate_to_module_name, dependency_infos, run_hygiene_checks};
+use crate::templates::{
+    discover_named_templates, discover_templates, discover_templates_from_template,
+    find_reconciler_manager_module, find_runtime_host_file, package_name, read_runtime_host_file,
+    read_template,
+};
+use crate::transform::transform_template;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub use builder::{ManagerConfig, RuntimeBuilder};
+pub use emit::{generate_runtime_source, template_include_path, try_generate_runtime_source};
+pub use error::{BuildError, SourceSpan};
+
+/// Main entry point: generate runtime.rs
+///
+/// Problems are reported to cargo as diagnostics (see [`BuildError::emit`])
+/// and fail the build without a build-script backtrace. Use
+/// [`RuntimeBuilder`] to name the host, templates or managers explicitly.
+pub fn generate_runtime() {
+    RuntimeBuilder::new().generate();
+}
+
+/// Generate runtime.rs into `OUT_DIR`, returning its path, or every problem
+/// found along the way.
+///
+/// Checks keep going past the first failure where they can, so one build
+/// reports everything wrong with the templates and their managers.
+pub fn try_generate_runtime() -> Result<PathBuf, Vec<BuildError>> {
+    RuntimeBuilder::new().try_generate()
+}
+
+/// A crate's runtime as codegen sees it: the effect managers it depends on,
+/// the templates its runtime host imports and the ports they declare.
+///
+/// Resolving a plan runs every hygiene and discovery check without writing
+/// anything, so tools can inspect a crate outside a build script.
+pub struct RuntimePlan {
+    spec: RuntimeSpec,
+}
+
+impl RuntimePlan {
+    /// Resolve the default runtime for the crate whose `Cargo.toml` is in
+    /// `manifest_dir`; see [`RuntimeBuilder::resolve`] for configured ones.
+    pub fn resolve(manifest_dir: &Path) -> Result<Self, Vec<BuildError>> {
+        RuntimeBuilder::new().manifest_dir(manifest_dir).resolve()
+    }
+
+    pub fn package_name(&self) -> &str {
+        &self.spec.package_name
+    }
+
+    /// Effect managers as `(crate name, request type)`.
+    pub fn managers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.spec
+            .effect_managers
+            .iter()
+            .map(|m| (m.crate_name.as_str(), m.request_type.as_str()))
+    }
+
+    /// Templates as `(crate name, module name, path)`.
+    pub fn templates(&self) -> impl Iterator<Item = (&str, &str, &Path)> {
+        self.spec.templates.iter().map(|t| {
+            (
+                t.crate_name.as_str(),
+                t.module_name.as_str(),
+                t.path.as_path(),
+            )
+        })
+    }
+
+    /// Ports as `(name, direction)`, the direction being `"incoming"` or `"outgoing"`.
+    pub fn ports(&self) -> impl Iterator<Item = (&str, &'static str)> {
+        self.spec.all_ports.iter().map(|p| match p {
+            PortSpec::Incoming(p) => (p.name.as_str(), "incoming"),
+            PortSpec::Outgoing(p) => (p.name.as_str(), "outgoing"),
+        })
+    }
+
+    /// Fictional helpers the templates import, as `(manager module, helper)`.
+    pub fn used_helpers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.spec
+            .used_helpers
+            .iter()
+            .map(|(m, h)| (m.as_str(), h.as_str()))
+    }
+
+    /// The generated `runtime.rs`.
+    pub fn runtime_source(&self) -> Result<String, Vec<BuildError>> {
+        emit::try_generate_runtime_source(&self.spec)
+    }
+
+    /// The runtime with each transformed template embedded in its module
+    /// instead of included from `OUT_DIR`, for the `runtime!` macro.
+    pub fn inline_runtime_source(&self) -> Result<String, Vec<BuildError>> {
+        emit::generate_source(&self.spec, emit::TemplateSource::Inline)
+    }
+
+    /// Each transformed template with the `OUT_DIR`-relative path the runtime
+    /// includes it from.
+    pub fn template_sources(&self) -> impl Iterator<Item = (String, &str)> {
+        self.spec
+            .templates
+            .iter()
+            .zip(&self.spec.transformed_templates)
+            .map(|(t, (_, code))| {
+                (
+                    template_include_path(&self.spec.templates_dir, t),
+                    code.as_str(),
+                )
+            })
+    }
+}
+
+/// Run every check and discovery step for the crate in `manifest_dir`,
+/// recording the files the result depends on in `watched`.
+fn resolve_spec(
+    manifest_dir: &Path,
+    config: &RuntimeConfig,
+    watched: &mut Vec<PathBuf>,
+) -> Result<RuntimeSpec, Vec<BuildError>> {
+    let cargo_toml_path = manifest_dir.join("Cargo.toml");
+    let package_name = package_name(&cargo_toml_path).map_err(|e| vec![e])?;
+    let mut errors = Vec::new();
+
+    // Hygiene checks on participating crates
+    run_hygiene_checks(&cargo_toml_path, &mut errors);
+
+    // Discover managers from dependencies
+    let mut managers =
+        managers::discover_managers(&cargo_toml_path, config.managers, watched, &mut errors);
+    if let Some(only) = config.only_managers {
+        managers::retain_managers(&mut managers, only, &cargo_toml_path, &mut errors);
+    }
+    watched.push(cargo_toml_path.clone());
+
+    // Root templates come from the apps the build script named, or else from
+    // the `use runtime::...` imports of the runtime host (the file that
+    // includes the output), which also shows which reconciler it runs with.
+    let (anchor_path, found_reconciler, mut templates) = if config.apps.is_empty() {
+        let marker = config.include_marker();
+        let host = match &config.host {
+            Some(path) => read_runtime_host_file(path, &marker),
+            None => find_runtime_host_file(manifest_dir, &marker),
+        };
+        let (runtime_host_path, _runtime_host_source, runtime_host_ast) = match host {
+            Ok(host) => host,
+            Err(e) => {
+                errors.push(e);
+                return Err(errors);
+            }
+        };
+        watched.push(runtime_host_path.clone());
+
+        let reconciler = find_reconciler_manager_module(
+            manifest_dir,
+            &runtime_host_ast,
+            config.host.is_none(),
+            &managers,
+        );
+
+        // The crate's own templates sit next to the host unless roots were configured.
+        let template_roots = if config.template_roots.is_empty() {
+            vec![runtime_host_path
+                .parent()
+                .expect("runtime host should have parent dir")
+                .to_path_buf()]
+        } else {
+            config.template_roots.clone()
+        };
+        let templates = discover_templates(
+            &runtime_host_path,
+            &runtime_host_ast,
+            &cargo_toml_path,
+            &template_roots,
+            &mut errors,
+        );
+        (runtime_host_path, reconciler, templates)
+    } else {
+        let template_roots = if config.template_roots.is_empty() {
+            vec![manifest_dir.join("src")]
+        } else {
+            config.template_roots.clone()
+        };
+        let templates =
+            discover_named_templates(&cargo_toml_path, config.apps, &template_roots, &mut errors);
+        (
+            cargo_toml_path.clone(),
+            Some("saucer_core".to_string()),
+            templates,
+        )
+    };
+
+    let reconciler_module = config
+        .reconciler
+        .map(crate_to_module_name)
+        .or(found_reconciler);
+    let reconciler_manager = match reconciler_module {
+        Some(module) => {
+            let manager = managers.iter().find(|m| m.module_name == module).cloned();
+            if manager.is_none() {
+                errors.push(
+                    BuildError::new(format!("the reconciler comes from `{}`, which is not a registered effect manager", module))
+                        .in_file(&anchor_path)
+                        .with_help("declare `effect_manager = true` under [package.metadata.saucer] in its Cargo.toml"),
+                );
+            }
+            manager
+        }
+        None => {
+            errors.push(
+                BuildError::new("no `Runtime::run` call with a reconciler was found")
+                    .in_file(&anchor_path)
+                    .with_help("invoke `Runtime::run` or `Runtime::new(...).run()` with the app functions and a reconciler from an effect manager, or name its crate with `RuntimeBuilder::reconciler`"),
+            );
+            None
+        }
+    };
+
+    let effect_managers: Vec<ManagerInfo> = managers
+        .iter()
+        .filter(|m| m.crate_name != "saucer-core")
+        .cloned()
+        .collect();
+
+    // Discover additional templates referenced from within templates via crate-qualified uses.
+    let dep_map: HashMap<String, hygiene::DepInfo> = match dependency_infos(&cargo_toml_path) {
+        Ok(deps) => deps
+            .into_iter()
+            .map(|d| (d.module_name.clone(), d))
+            .collect(),
+        // Already reported by the hygiene checks.
+        Err(_) => HashMap::new(),
+    };
+    let mut idx = 0;
+    while idx < templates.len() {
+        let t = templates[idx].clone();
+        discover_templates_from_template(&t, &dep_map, &mut templates, &mut errors);
+        idx += 1;
+    }
+
+    watched.extend(templates.iter().map(|t| t.path.clone()));
+
+    // Parse ports on root templates; reject ports in transitive templates.
+    for t in templates.iter_mut() {
+        let Ok((_, ast)) = read_template(&t.path) else {
+            // Already reported during discovery.
+            continue;
+        };
+        let parsed_ports = ports::parse_ports(&t.path, &ast, &mut errors);
+        if !parsed_ports.is_empty() {
+            if !t.is_root {
+                errors.push(
+                    BuildError::new("ports may only be declared in templates imported directly by the runtime host")
+                        .in_file(&t.path)
+                        .with_help("move the #[port] functions into a root template"),
+                );
+                continue;
+            }
+            t.ports = parsed_ports;
+        }
+    }
+
+    // Transform templates and collect which helpers were actually used
+    let dep_template_catalog: Vec<(String, String)> = templates
+        .iter()
+        .map(|t| (crate_to_module_name(&t.crate_name), t.module_name.clone()))
+        .collect();
+
+    let mut all_used_helpers: Vec<(String, String)> = Vec::new();
+    let transformed_templates: Vec<_> = templates
+        .iter_mut()
+        .map(|t| {
+            let transformed = transform_template(
+                &t.path,
+                &managers,
+                &dep_template_catalog,
+                &t.ports,
+                &mut errors,
+            );
+            t.used_helpers = transformed.used_helpers.clone();
+            all_used_helpers.extend(transformed.used_helpers.into_iter());
+            (t.module_name.clone(), transformed.code)
+        })
+        .collect();
+
+    emit::check_helpers(&effect_managers, &all_used_helpers, &mut errors);
+
+    if templates.is_empty() && errors.is_empty() {
+        errors.push(
+            BuildError::new("no templates are imported by the runtime host")
+                .in_file(&anchor_path)
+                .with_help("import the app template, e.g. `use runtime::<crate>::app;`"),
+        );
+    }
+    let reconciler_manager = match reconciler_manager {
+        Some(manager) if errors.is_empty() => manager,
+        _ => return Err(errors),
+    };
+
+    let all_ports: Vec<PortSpec> = templates.iter().flat_map(|t| t.ports.clone()).collect();
+
+    let has_outgoing_ports = all_ports.iter().any(|p| matches!(p, PortSpec::Outgoing(_)));
+
+    // Choose the primary app template (first root from the current crate) for Msg pathing.
+    let app_template = templates
+        .iter()
+        .find(|t| {
+            t.is_root && crate_to_module_name(&t.crate_name) == crate_to_module_name(&package_name)
+        })
+        .unwrap_or(&templates[0]);
+    let app_crate_ident = format_ident!("{}", crate_to_module_name(&app_template.crate_name));
+    let app_module_ident = format_ident!("{}", app_template.module_name);
+    let app_msg_path: TokenStream = quote! { #app_crate_ident::#app_module_ident::Msg };
+
+    let ports_with_paths: Vec<(PortSpec, proc_macro2::Ident, proc_macro2::Ident)> = templates
+        .iter()
+        .filter(|t| t.is_root)
+        .flat_map(|t| {
+            let crate_ident = format_ident!("{}", crate_to_module_name(&t.crate_name));
+            let module_ident = format_ident!("{}", t.module_name);
+            t.ports
+                .iter()
+                .cloned()
+                .map(move |p| (p, crate_ident.clone(), module_ident.clone()))
+        })
+        .collect();
+
+    Ok(RuntimeSpec {
+        package_name,
+        templates_dir: config.templates_dir(),
+        managers,
+        effect_managers,
+        reconciler_manager,
+        templates,
+        transformed_templates,
+        used_helpers: all_used_helpers,
+        all_ports,
+        ports_with_paths,
+        app_msg_path,
+        has_outgoing_ports,
+    })
+}
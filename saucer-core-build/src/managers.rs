@@ -0,0 +1,241 @@
+use std::path::{Path, PathBuf};
+
+use toml::Value;
+
+use crate::ast::ManagerInfo;
+use crate::builder::ManagerConfig;
+use crate::error::BuildError;
+use crate::hygiene::crate_to_module_name;
+use crate::metadata::{self, Dependency};
+
+/// Discover effect managers from Cargo.toml dependencies, plus those the
+/// build script declared in `configured`, which take precedence over a
+/// dependency's own metadata. Misconfigured managers are reported to
+/// `errors` and left out; the manifests of the rest are added to `watched`.
+pub fn discover_managers(
+    cargo_toml_path: &Path,
+    configured: &[ManagerConfig],
+    watched: &mut Vec<PathBuf>,
+    errors: &mut Vec<BuildError>,
+) -> Vec<ManagerInfo> {
+    let mut managers = Vec::new();
+
+    let deps = match metadata::direct_dependencies(cargo_toml_path) {
+        Ok(deps) => deps,
+        Err(e) => {
+            errors.push(e);
+            return managers;
+        }
+    };
+
+    for config in configured {
+        if !deps.iter().any(|dep| dep.name == config.crate_name) {
+            errors.push(
+                BuildError::new(format!(
+                    "effect manager `{}` is not a dependency of this crate",
+                    config.crate_name
+                ))
+                .in_file(cargo_toml_path)
+                .with_help("add it under [dependencies]"),
+            );
+        }
+    }
+
+    for dep in deps {
+        let dep_cargo_path = dep.manifest_path.clone();
+
+        if let Some(config) = configured.iter().find(|c| c.crate_name == dep.name) {
+            if let Err(e) = check_command_stub(&dep.lib_path) {
+                errors.push(e);
+                continue;
+            }
+            let info = manager_info(
+                &dep,
+                config.request_type.clone(),
+                config.subscription_type.clone(),
+                config.manager_type.clone(),
+                config.self_msg_type.clone(),
+                config.spawn,
+            );
+            add_manager(&mut managers, watched, info);
+            continue;
+        }
+
+        // Read dependency's Cargo.toml
+        let dep_content = match std::fs::read_to_string(&dep_cargo_path) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+
+        let dep_toml: Value = match dep_content.parse() {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+
+        // Check for [package.metadata.saucer] with effect_manager = true
+        let saucer_meta = match dep_toml
+            .get("package")
+            .and_then(|p| p.get("metadata"))
+            .and_then(|m| m.get("saucer"))
+        {
+            Some(meta) => meta,
+            None => continue,
+        };
+
+        let is_effect_manager = saucer_meta
+            .get("effect_manager")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if !is_effect_manager {
+            continue;
+        }
+
+        let request_type = saucer_meta
+            .get("request_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Request")
+            .to_string();
+
+        let subscription_type = saucer_meta
+            .get("subscription_type")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let manager_type = saucer_meta
+            .get("manager_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Manager")
+            .to_string();
+
+        let self_msg_type = saucer_meta
+            .get("self_msg_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("()")
+            .to_string();
+
+        let spawn = saucer_meta
+            .get("spawn")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if let Err(e) = check_command_stub(&dep.lib_path) {
+            errors.push(e);
+            continue;
+        }
+
+        let info = manager_info(
+            &dep,
+            request_type,
+            subscription_type,
+            manager_type,
+            self_msg_type,
+            spawn,
+        );
+        add_manager(&mut managers, watched, info);
+    }
+
+    managers
+}
+
+/// Keep saucer-core and the managers named in `only` (by crate or module
+/// name), reporting names that are not discovered managers.
+pub fn retain_managers(
+    managers: &mut Vec<ManagerInfo>,
+    only: &[String],
+    cargo_toml_path: &Path,
+    errors: &mut Vec<BuildError>,
+) {
+    let only: Vec<String> = only.iter().map(|name| crate_to_module_name(name)).collect();
+    for name in &only {
+        if !managers.iter().any(|m| &m.module_name == name) {
+            errors.push(
+                BuildError::new(format!(
+                    "`{}` is not an effect manager this crate depends on",
+                    name
+                ))
+                .in_file(cargo_toml_path)
+                .with_help(
+                    "depend on it and declare `effect_manager = true` under [package.metadata.saucer] in its Cargo.toml",
+                ),
+            );
+        }
+    }
+    managers.retain(|m| m.crate_name == "saucer-core" || only.contains(&m.module_name));
+}
+
+/// Record a discovered manager, watching the files its generated code
+/// depends on: the manifest's metadata and the `requests.rs` helpers.
+fn add_manager(managers: &mut Vec<ManagerInfo>, watched: &mut Vec<PathBuf>, manager: ManagerInfo) {
+    watched.push(manager.manifest_path.clone());
+    let requests_path = manager.requests_path();
+    if requests_path.exists() {
+        watched.push(requests_path);
+    }
+    managers.push(manager);
+}
+
+fn manager_info(
+    dep: &Dependency,
+    request_type: String,
+    subscription_type: Option<String>,
+    manager_type: String,
+    self_msg_type: String,
+    spawn: bool,
+) -> ManagerInfo {
+    // Derive variant name from manager type (e.g., "TimeManager" -> "Time")
+    let variant = manager_type
+        .strip_suffix("Manager")
+        .unwrap_or(&manager_type)
+        .to_string();
+
+    ManagerInfo {
+        crate_name: dep.name.clone(),
+        module_name: dep.module_name.clone(),
+        variant,
+        request_type,
+        subscription_type,
+        manager_type,
+        self_msg_type,
+        spawn,
+        lib_path: dep.lib_path.clone(),
+        manifest_path: dep.manifest_path.clone(),
+    }
+}
+
+fn check_command_stub(lib_path: &Path) -> Result<(), BuildError> {
+    let command_path = lib_path
+        .parent()
+        .expect("lib.rs has a parent")
+        .join("command.rs");
+    if !command_path.exists() {
+        return Ok(());
+    }
+
+    let content =
+        std::fs::read_to_string(&command_path).map_err(|e| BuildError::io(&command_path, &e))?;
+
+    let trimmed = content.trim();
+    if trimmed.is_empty() {
+        return Ok(());
+    }
+
+    let has_breadcrumb = trimmed
+        .lines()
+        .any(|l| l.contains("commands are generated") || l.contains("requests.rs"));
+
+    let all_comments = trimmed
+        .lines()
+        .all(|l| l.trim().is_empty() || l.trim_start().starts_with("//"));
+
+    if !all_comments || !has_breadcrumb {
+        return Err(
+            BuildError::new("effect manager has a real src/command.rs")
+                .in_file(&command_path)
+                .with_help(
+                    "leave command.rs empty except for a breadcrumb comment pointing to src/requests.rs",
+                ),
+        );
+    }
+    Ok(())
+}
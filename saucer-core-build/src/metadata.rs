@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+use serde_json::Value;
+
+use crate::error::BuildError;
+
+/// A direct (normal) dependency of the crate being built.
+///
+/// Located through `cargo metadata`, so registry, git, workspace-inherited
+/// (`workspace = true`) and renamed (`package = "..."`) dependencies resolve
+/// the same way `path` dependencies do.
+#[derive(Debug, Clone)]
+pub struct Dependency {
+    /// Name the dependency is declared under (the rename, if any).
+    pub name: String,
+    /// Name the code refers to it by (`name` with `-` replaced by `_`).
+    pub module_name: String,
+    /// Path to the dependency's Cargo.toml.
+    pub manifest_path: PathBuf,
+    /// Root of the dependency's library target (`src/lib.rs` unless its
+    /// `[lib] path` says otherwise).
+    pub lib_path: PathBuf,
+}
+
+impl Dependency {
+    /// Directory containing the dependency's Cargo.toml.
+    pub fn dir(&self) -> &Path {
+        self.manifest_path
+            .parent()
+            .expect("manifest path should have a parent")
+    }
+}
+
+/// Direct normal dependencies of the package whose manifest is `cargo_toml_path`,
+/// sorted by declared name. Results are cached per manifest since build
+/// scripts ask several times; inside the `runtime!` macro the cache would
+/// outlive the expansion in rustc's process and miss manifest edits, so
+/// `cargo metadata` runs every time.
+pub fn direct_dependencies(cargo_toml_path: &Path) -> Result<Vec<Dependency>, BuildError> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, Vec<Dependency>>>> = OnceLock::new();
+    let key = cargo_toml_path
+        .canonicalize()
+        .map_err(|e| BuildError::io(cargo_toml_path, &e))?;
+    if proc_macro::is_available() {
+        return resolve_dependencies(&key);
+    }
+    let mut cache = CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .expect("dependency cache mutex poisoned");
+    if let Some(deps) = cache.get(&key) {
+        return Ok(deps.clone());
+    }
+    let deps = resolve_dependencies(&key)?;
+    cache.insert(key, deps.clone());
+    Ok(deps)
+}
+
+fn resolve_dependencies(manifest_path: &Path) -> Result<Vec<Dependency>, BuildError> {
+    let metadata = cargo_metadata(manifest_path)?;
+
+    let packages = metadata["packages"]
+        .as_array()
+        .ok_or_else(|| malformed("has no `packages`", manifest_path))?;
+    let package = packages
+        .iter()
+        .find(|p| p["manifest_path"].as_str().map(Path::new) == Some(manifest_path))
+        .ok_or_else(|| {
+            BuildError::new("`cargo metadata` did not report a package for this manifest")
+                .in_file(manifest_path)
+        })?;
+    let package_id = package["id"]
+        .as_str()
+        .ok_or_else(|| malformed("reported a package without an `id`", manifest_path))?;
+
+    // Declared names, keyed by the extern crate name the resolver reports.
+    let declared: HashMap<String, String> = package["dependencies"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|d| {
+            let name = d["rename"].as_str().or_else(|| d["name"].as_str())?;
+            Some((name.replace('-', "_"), name.to_string()))
+        })
+        .collect();
+
+    let node = metadata["resolve"]["nodes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|n| n["id"].as_str() == Some(package_id))
+        .ok_or_else(|| {
+            BuildError::new("`cargo metadata` did not resolve dependencies for this manifest")
+                .in_file(manifest_path)
+        })?;
+
+    let mut deps: Vec<Dependency> = node["deps"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|d| {
+            d["dep_kinds"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .any(|k| k["kind"].is_null())
+        })
+        .map(|d| {
+            let module_name = d["name"].as_str().ok_or_else(|| {
+                malformed("reported a dependency without a `name`", manifest_path)
+            })?;
+            let pkg_id = d["pkg"].as_str().ok_or_else(|| {
+                malformed(
+                    &format!("reported dependency `{}` without a `pkg`", module_name),
+                    manifest_path,
+                )
+            })?;
+            let dep_package = packages
+                .iter()
+                .find(|p| p["id"].as_str() == Some(pkg_id))
+                .ok_or_else(|| {
+                    malformed(&format!("is missing package `{}`", pkg_id), manifest_path)
+                })?;
+            let dep_manifest_path =
+                PathBuf::from(dep_package["manifest_path"].as_str().ok_or_else(|| {
+                    malformed(
+                        &format!("reported package `{}` without a `manifest_path`", pkg_id),
+                        manifest_path,
+                    )
+                })?);
+            let lib_path = dep_package["targets"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|t| t["kind"].as_array().into_iter().flatten().any(is_lib_kind))
+                .and_then(|t| t["src_path"].as_str())
+                .map(PathBuf::from)
+                .unwrap_or_else(|| dep_manifest_path.with_file_name("src").join("lib.rs"));
+            Ok(Dependency {
+                name: declared
+                    .get(module_name)
+                    .cloned()
+                    .unwrap_or_else(|| module_name.to_string()),
+                module_name: module_name.to_string(),
+                manifest_path: dep_manifest_path,
+                lib_path,
+            })
+        })
+        .collect::<Result<_, BuildError>>()?;
+    deps.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(deps)
+}
+
+/// Target kinds a dependent crate can link against.
+fn is_lib_kind(kind: &Value) -> bool {
+    matches!(kind.as_str(), Some("lib" | "rlib" | "dylib" | "proc-macro"))
+}
+
+fn cargo_metadata(manifest_path: &Path) -> Result<Value, BuildError> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let mut cmd = Command::new(cargo);
+    cmd.args(["metadata", "--format-version", "1", "--manifest-path"])
+        .arg(manifest_path);
+    // Build scripts know the target; filtering avoids resolving other platforms' deps.
+    if let Ok(target) = std::env::var("TARGET") {
+        cmd.args(["--filter-platform", &target]);
+    }
+    let output = cmd.output().map_err(|e| {
+        BuildError::new(format!("failed to run `cargo metadata`: {}", e)).in_file(manifest_path)
+    })?;
+    if !output.status.success() {
+        return Err(BuildError::new(format!(
+            "`cargo metadata` failed:\n{}",
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ))
+        .in_file(manifest_path));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| {
+        BuildError::new(format!("failed to parse `cargo metadata` output: {}", e))
+            .in_file(manifest_path)
+    })
+}
+
+/// Error for `cargo metadata` output that lacks something it always reports.
+fn malformed(what: &str, manifest_path: &Path) -> BuildError {
+    BuildError::new(format!("`cargo metadata` output {}", what)).in_file(manifest_path)
+}
@@ -0,0 +1,139 @@
+use std::path::Path;
+
+use crate::ast::{IncomingPort, OutgoingPort, PortDirection, PortSpec};
+use crate::error::BuildError;
+use quote::ToTokens;
+use syn::{FnArg, Item, Pat, ReturnType};
+
+/// Parse ports declared in the template at `path`. Ports with bad signatures
+/// are reported to `errors` and skipped.
+pub fn parse_ports(path: &Path, file: &syn::File, errors: &mut Vec<BuildError>) -> Vec<PortSpec> {
+    let mut ports = Vec::new();
+    for item in &file.items {
+        if let Item::Fn(func) = item {
+            let has_port_attr = func.attrs.iter().any(|a| a.path().is_ident("port"));
+            if !has_port_attr {
+                continue;
+            }
+            match parse_port(path, func) {
+                Ok(spec) => ports.push(spec),
+                Err(e) => errors.push(e),
+            }
+        }
+    }
+    ports
+}
+
+fn parse_port(path: &Path, func: &syn::ItemFn) -> Result<PortSpec, BuildError> {
+    let error = |message: String| BuildError::new(message).in_file(path);
+    let name = func.sig.ident.to_string();
+    let ret_ty = match &func.sig.output {
+        ReturnType::Type(_, ty) => ty.as_ref(),
+        ReturnType::Default => {
+            return Err(
+                error(format!("port function `{}` must have a return type", name))
+                    .at(&func.sig)
+                    .with_help(
+                        "return Sub<Msg> for an incoming port or Cmd<Msg> for an outgoing one",
+                    ),
+            )
+        }
+    };
+    let direction = match ret_ty {
+        syn::Type::Path(tp)
+            if tp
+                .path
+                .segments
+                .last()
+                .is_some_and(|seg| seg.ident == "Cmd") =>
+        {
+            PortDirection::Outgoing
+        }
+        syn::Type::Path(tp)
+            if tp
+                .path
+                .segments
+                .last()
+                .is_some_and(|seg| seg.ident == "Sub") =>
+        {
+            PortDirection::Incoming
+        }
+        _ => {
+            return Err(error(format!(
+                "port function `{}` must return Sub<Msg> (incoming) or Cmd<Msg> (outgoing)",
+                name
+            ))
+            .at(ret_ty))
+        }
+    };
+
+    let mut args = Vec::new();
+    for arg in func.sig.inputs.iter() {
+        match arg {
+            FnArg::Typed(pt) => {
+                let ident = match &*pt.pat {
+                    Pat::Ident(id) => id.ident.to_string(),
+                    _ => {
+                        return Err(error(format!(
+                            "port function `{}` uses an unsupported pattern argument",
+                            name
+                        ))
+                        .at(&pt.pat)
+                        .with_help("bind each argument to a plain name"))
+                    }
+                };
+                let ty = pt.ty.to_token_stream().to_string();
+                args.push((ident, ty));
+            }
+            FnArg::Receiver(receiver) => {
+                return Err(error(format!(
+                    "port function `{}` must be free, not a method",
+                    name
+                ))
+                .at(receiver))
+            }
+        }
+    }
+
+    Ok(match direction {
+        PortDirection::Incoming => PortSpec::Incoming(IncomingPort { name, args }),
+        PortDirection::Outgoing => PortSpec::Outgoing(OutgoingPort { name, args }),
+    })
+}
+
+pub fn payload_type_tokens(port: &PortSpec) -> proc_macro2::TokenStream {
+    let args: &Vec<(String, String)> = match port {
+        PortSpec::Incoming(p) => &p.args,
+        PortSpec::Outgoing(p) => &p.args,
+    };
+    let ty: String = match args.len() {
+        0 => "()".to_string(),
+        1 => args[0].1.clone(),
+        _ => format!(
+            "({})",
+            args.iter()
+                .map(|(_, t)| t.clone())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    ty.parse().expect("payload type should parse")
+}
+
+pub(crate) fn to_camel(s: &str) -> String {
+    let mut out = String::new();
+    let mut upper = true;
+    for c in s.chars() {
+        if c == '_' {
+            upper = true;
+            continue;
+        }
+        if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -0,0 +1,355 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::ast::{ManagerInfo, PortSpec};
+use crate::ports;
+
+/// Generate the Request enum (and PortsRequest) plus map() helper, redacted Debug,
+/// and the `Describe`/`AsRequest` impls tests inspect commands through.
+pub fn generate_request_enum(managers: &[ManagerInfo], ports: &[PortSpec]) -> TokenStream {
+    let variants: Vec<_> = managers
+        .iter()
+        .map(|m| {
+            let variant_ident = format_ident!("{}", m.variant);
+            let module_ident = format_ident!("{}", m.module_name);
+            let request_ident = format_ident!("{}", m.request_type);
+            quote! {
+                #variant_ident(::#module_ident::#request_ident<Msg>)
+            }
+        })
+        .collect();
+
+    let variant_idents: Vec<_> = managers
+        .iter()
+        .map(|m| format_ident!("{}", m.variant))
+        .collect();
+
+    let ports_variant = if ports.iter().any(|p| matches!(p, PortSpec::Outgoing(_))) {
+        Some(quote! { Ports(PortsRequest, std::marker::PhantomData<Msg>) })
+    } else {
+        None
+    };
+
+    let mut request_variants: Vec<TokenStream> = Vec::new();
+    request_variants.push(quote! { Core(saucer_core::CoreRequest) });
+    request_variants.extend(variants.into_iter());
+    if let Some(p) = &ports_variant {
+        request_variants.push(p.clone());
+    }
+
+    let ports_variant_idents: Vec<_> = ports
+        .iter()
+        .filter_map(|p| match p {
+            PortSpec::Outgoing(p) => Some(format_ident!("{}", ports::to_camel(&p.name))),
+            _ => None,
+        })
+        .collect();
+
+    let map_arms: Vec<_> = managers
+        .iter()
+        .map(|m| {
+            let variant_ident = format_ident!("{}", m.variant);
+            quote! { Request::#variant_ident(req) => Request::#variant_ident(req.map(f)) }
+        })
+        .collect();
+
+    let mut map_match_arms: Vec<TokenStream> =
+        vec![quote! { Request::Core(core) => Request::Core(core) }];
+    map_match_arms.extend(map_arms);
+    if ports_variant.is_some() {
+        map_match_arms.push(quote! {
+            Request::Ports(p, _) => Request::Ports(p, std::marker::PhantomData)
+        });
+    }
+
+    let map_impl = quote! {
+        #[allow(dead_code)]
+        impl<Msg: 'static> Request<Msg> {
+            #[allow(dead_code)]
+            fn map<Msg2>(self, f: impl Fn(Msg) -> Msg2 + Send + Sync + Clone + 'static) -> Request<Msg2> {
+                let _ = &f;
+                match self {
+                    #(#map_match_arms),*
+                }
+            }
+        }
+    };
+
+    let ports_request_enum = if ports_variant.is_some() {
+        let variants: Vec<_> = ports
+            .iter()
+            .filter_map(|p| match p {
+                PortSpec::Outgoing(p) => Some(p),
+                _ => None,
+            })
+            .map(|p| {
+                let v = format_ident!("{}", ports::to_camel(&p.name));
+                let ty: TokenStream = ports::payload_type_tokens(&PortSpec::Outgoing(p.clone()));
+                quote! { #v { value: #ty } }
+            })
+            .collect();
+        quote! {
+            #[derive(Clone)]
+            pub enum PortsRequest {
+                #(#variants),*
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let ports_request_debug = if ports_variant.is_some() {
+        quote! {
+            impl std::fmt::Debug for PortsRequest {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    match self {
+                        #(PortsRequest::#ports_variant_idents { .. } => f.write_str(concat!("PortsRequest::", stringify!(#ports_variant_idents))),)*
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let match_arms: Vec<_> = variant_idents
+        .iter()
+        .map(|ident| {
+            quote! {
+                Request::#ident(req) => f.debug_tuple(concat!("Request::", stringify!(#ident)))
+                    .field(req)
+                    .finish(),
+            }
+        })
+        .collect();
+
+    let describe_arms: Vec<_> = managers
+        .iter()
+        .map(|m| {
+            let variant_ident = format_ident!("{}", m.variant);
+            let module_name = &m.module_name;
+            quote! { Request::#variant_ident(req) => saucer_core::RequestSnapshot::of(#module_name, req), }
+        })
+        .collect();
+
+    // Only managers whose requests describe themselves get a `Describe`
+    // impl; others still compile, they just can't be snapshotted.
+    let describe_bounds: Vec<_> = managers
+        .iter()
+        .map(|m| {
+            let module_ident = format_ident!("{}", m.module_name);
+            let request_ident = format_ident!("{}", m.request_type);
+            quote! { ::#module_ident::#request_ident<Msg>: saucer_core::RequestFields, }
+        })
+        .collect();
+
+    let as_request_impls: Vec<_> = managers
+        .iter()
+        .map(|m| {
+            let variant_ident = format_ident!("{}", m.variant);
+            let module_ident = format_ident!("{}", m.module_name);
+            let request_ident = format_ident!("{}", m.request_type);
+            quote! {
+                impl<Msg> saucer_core::AsRequest<::#module_ident::#request_ident<Msg>> for Request<Msg> {
+                    fn as_request(&self) -> Option<&::#module_ident::#request_ident<Msg>> {
+                        match self {
+                            Request::#variant_ident(req) => Some(req),
+                            _ => None,
+                        }
+                    }
+                }
+            }
+        })
+        .collect();
+
+    let ports_describe_arm = if ports_variant.is_some() {
+        quote! { Request::Ports(p, _) => saucer_core::RequestSnapshot::of("ports", p), }
+    } else {
+        quote! {}
+    };
+
+    let ports_request_fields = if ports_variant.is_some() {
+        quote! {
+            impl saucer_core::RequestFields for PortsRequest {
+                fn variant(&self) -> &'static str {
+                    match self {
+                        #(PortsRequest::#ports_variant_idents { .. } => stringify!(#ports_variant_idents),)*
+                    }
+                }
+
+                // Port payloads need not be `Debug`, matching `PortsRequest`'s own `Debug`.
+                fn fields(&self) -> Vec<(&'static str, String)> {
+                    Vec::new()
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let ports_debug_arm = if ports_variant.is_some() {
+        quote! { Request::Ports(p, _) => f.debug_tuple("Request::Ports").field(p).finish(), }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        #ports_request_enum
+        #[allow(dead_code)]
+        #[derive(Clone)]
+        pub enum Request<Msg> {
+            #(#request_variants),*
+        }
+
+        impl<Msg> std::fmt::Debug for Request<Msg> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    Request::Core(_) => f.write_str("Request::Core"),
+                    #(#match_arms)*
+                    #ports_debug_arm
+                }
+            }
+        }
+
+        #ports_request_debug
+
+        impl<Msg> saucer_core::Describe for Request<Msg>
+        where
+            #(#describe_bounds)*
+        {
+            fn describe(&self) -> saucer_core::RequestSnapshot {
+                match self {
+                    Request::Core(req) => saucer_core::RequestSnapshot::of("saucer_core", req),
+                    #(#describe_arms)*
+                    #ports_describe_arm
+                }
+            }
+        }
+
+        impl<Msg> saucer_core::AsRequest<saucer_core::CoreRequest> for Request<Msg> {
+            fn as_request(&self) -> Option<&saucer_core::CoreRequest> {
+                #[allow(unreachable_patterns)] // a runtime without managers or ports
+                match self {
+                    Request::Core(req) => Some(req),
+                    _ => None,
+                }
+            }
+        }
+
+        #(#as_request_impls)*
+
+        #ports_request_fields
+
+        #map_impl
+    }
+}
+
+/// Generate the unified SelfMsg type (enum over managers with self-msgs).
+pub fn generate_self_msg_type(managers: &[ManagerInfo]) -> TokenStream {
+    let with_self_msgs: Vec<_> = managers
+        .iter()
+        .filter(|m| m.self_msg_type != "()")
+        .collect();
+
+    if with_self_msgs.is_empty() {
+        quote! { pub type SelfMsg = (); }
+    } else {
+        let variants: Vec<_> = with_self_msgs
+            .iter()
+            .map(|m| {
+                let variant_ident = format_ident!("{}", m.variant);
+                let module_ident = format_ident!("{}", m.module_name);
+                let ty_ident = format_ident!("{}", m.self_msg_type);
+                quote! { #variant_ident(::#module_ident::#ty_ident) }
+            })
+            .collect();
+
+        quote! {
+            #[allow(dead_code)]
+            #[derive(Clone, Debug)]
+            pub enum SelfMsg {
+                #(#variants),*
+            }
+        }
+    }
+}
+
+/// Generate the Subscription enum (over managers declaring a subscription type)
+/// plus map() helper and redacted Debug.
+pub fn generate_subscription_enum(managers: &[ManagerInfo]) -> TokenStream {
+    let with_subs: Vec<_> = managers
+        .iter()
+        .filter_map(|m| m.subscription_type.as_ref().map(|s| (m, s)))
+        .collect();
+
+    if with_subs.is_empty() {
+        // Keep the type around so `Sub<Msg>` always resolves, even without subscribing managers.
+        return quote! {
+            #[allow(dead_code)]
+            pub enum Subscription<Msg> {
+                Never(std::convert::Infallible, std::marker::PhantomData<Msg>),
+            }
+
+            impl<Msg> std::fmt::Debug for Subscription<Msg> {
+                fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    match self {
+                        Subscription::Never(never, _) => match *never {},
+                    }
+                }
+            }
+
+            #[allow(dead_code)]
+            impl<Msg: 'static> Subscription<Msg> {
+                #[allow(dead_code)]
+                fn map<Msg2>(self, _f: impl Fn(Msg) -> Msg2 + Send + Sync + Clone + 'static) -> Subscription<Msg2> {
+                    match self {
+                        Subscription::Never(never, _) => match never {},
+                    }
+                }
+            }
+        };
+    }
+
+    let variants: Vec<_> = with_subs
+        .iter()
+        .map(|(m, sub_type)| {
+            let variant_ident = format_ident!("{}", m.variant);
+            let module_ident = format_ident!("{}", m.module_name);
+            let sub_ident = format_ident!("{}", sub_type);
+            quote! { #variant_ident(::#module_ident::#sub_ident<Msg>) }
+        })
+        .collect();
+
+    let variant_idents: Vec<_> = with_subs
+        .iter()
+        .map(|(m, _)| format_ident!("{}", m.variant))
+        .collect();
+
+    quote! {
+        #[allow(dead_code)]
+        #[derive(Clone)]
+        pub enum Subscription<Msg> {
+            #(#variants),*
+        }
+
+        impl<Msg> std::fmt::Debug for Subscription<Msg> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    #(Subscription::#variant_idents(sub) => f.debug_tuple(concat!("Subscription::", stringify!(#variant_idents)))
+                        .field(sub)
+                        .finish(),)*
+                }
+            }
+        }
+
+        #[allow(dead_code)]
+        impl<Msg: 'static> Subscription<Msg> {
+            #[allow(dead_code)]
+            fn map<Msg2>(self, f: impl Fn(Msg) -> Msg2 + Send + Sync + Clone + 'static) -> Subscription<Msg2> {
+                match self {
+                    #(Subscription::#variant_idents(sub) => Subscription::#variant_idents(sub.map(f)),)*
+                }
+            }
+        }
+    }
+}
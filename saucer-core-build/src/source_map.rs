@@ -0,0 +1,251 @@
+//! Byte ranges of parsed tokens, found from the source text alone.
+//!
+//! Inside the `runtime!` macro, tokens parsed from a template carry the
+//! macro call site's span rather than a position in the template. A
+//! [`SourceMap`] lexes the template itself and pairs every token with its
+//! byte range, so edits and diagnostics can be placed by matching a syntax
+//! node's tokens against the file instead of reading its spans.
+
+use std::ops::Range;
+
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::ToTokens;
+
+/// The tokens of one source file, each with the bytes it came from.
+pub struct SourceMap {
+    tokens: Vec<String>,
+    ranges: Vec<Range<usize>>,
+}
+
+impl SourceMap {
+    /// Map `source`, or `None` when it does not tokenize or the lexer here
+    /// disagrees with `proc_macro2` about its tokens.
+    pub fn new(source: &str) -> Option<Self> {
+        let stream: TokenStream = source.parse().ok()?;
+        let mut tokens = Vec::new();
+        flatten(stream, &mut tokens);
+        let ranges = lex(source)?;
+        let aligned = tokens.len() == ranges.len()
+            && tokens.iter().zip(&ranges).all(|(token, range)| {
+                let text = &source[range.clone()];
+                // Doc comments stand for several `#[doc = ..]` tokens.
+                text.starts_with('/') || text == token
+            });
+        aligned.then_some(Self { tokens, ranges })
+    }
+
+    /// Token indices of the first occurrence of `node` at or after token `from`.
+    pub fn find(&self, node: &impl ToTokens, from: usize) -> Option<Range<usize>> {
+        let mut needle = Vec::new();
+        flatten(node.to_token_stream(), &mut needle);
+        if needle.is_empty() || from > self.tokens.len() {
+            return None;
+        }
+        self.tokens[from..]
+            .windows(needle.len())
+            .position(|window| window == needle.as_slice())
+            .map(|i| from + i..from + i + needle.len())
+    }
+
+    /// The source bytes covered by a non-empty run of tokens.
+    pub fn bytes(&self, tokens: Range<usize>) -> Range<usize> {
+        self.ranges[tokens.start].start..self.ranges[tokens.end - 1].end
+    }
+
+    /// The source bytes of the first occurrence of `node`.
+    pub fn locate(&self, node: &impl ToTokens) -> Option<Range<usize>> {
+        self.find(node, 0).map(|tokens| self.bytes(tokens))
+    }
+}
+
+/// Token texts in source order, with each group's delimiters as tokens of
+/// their own.
+fn flatten(stream: TokenStream, out: &mut Vec<String>) {
+    for tree in stream {
+        match tree {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                if !open.is_empty() {
+                    out.push(open.to_string());
+                }
+                flatten(group.stream(), out);
+                if !close.is_empty() {
+                    out.push(close.to_string());
+                }
+            }
+            TokenTree::Punct(punct) => out.push(punct.as_char().to_string()),
+            other => out.push(other.to_string()),
+        }
+    }
+}
+
+/// Byte ranges of the tokens `flatten` yields for `source`: one per
+/// identifier, literal, punctuation character and delimiter, two for a
+/// lifetime (`'` and its name), and six (`#[doc = ..]`) or seven
+/// (`#![doc = ..]`) sharing the range of a doc comment.
+fn lex(source: &str) -> Option<Vec<Range<usize>>> {
+    let bytes = source.as_bytes();
+    let at = |i: usize| bytes.get(i).copied().unwrap_or(0);
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let c = source[i..].chars().next()?;
+        if c.is_whitespace() {
+            i += c.len_utf8();
+        } else if source[i..].starts_with("//") {
+            i = source[i..].find('\n').map_or(source.len(), |n| i + n);
+            let text = &source[start..i];
+            let doc = if text.starts_with("///") && !text.starts_with("////") {
+                6
+            } else if text.starts_with("//!") {
+                7
+            } else {
+                0
+            };
+            ranges.extend(std::iter::repeat_n(start..i, doc));
+        } else if source[i..].starts_with("/*") {
+            let mut depth = 0;
+            while i < bytes.len() {
+                if bytes[i..].starts_with(b"/*") {
+                    depth += 1;
+                    i += 2;
+                } else if bytes[i..].starts_with(b"*/") {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+            if depth != 0 {
+                return None;
+            }
+            let text = &source[start..i];
+            let doc = if text.starts_with("/**") && !text.starts_with("/***") && text != "/**/" {
+                6
+            } else if text.starts_with("/*!") {
+                7
+            } else {
+                0
+            };
+            ranges.extend(std::iter::repeat_n(start..i, doc));
+        } else if let Some(end) = prefixed_literal(source, i) {
+            i = end;
+            ranges.push(start..i);
+        } else if c == '_' || c.is_alphabetic() {
+            if source[i..].starts_with("r#") {
+                i += 2;
+            }
+            i = ident_end(source, i);
+            ranges.push(start..i);
+        } else if c.is_ascii_digit() {
+            i = number_end(source, i);
+            ranges.push(start..i);
+        } else if c == '"' {
+            i = ident_end(source, quoted_end(source, i + 1, b'"')?);
+            ranges.push(start..i);
+        } else if c == '\'' {
+            let next = source[i + 1..].chars().next()?;
+            let after = i + 1 + next.len_utf8();
+            if next == '\\' || at(after) == b'\'' {
+                i = quoted_end(source, i + 1, b'\'')?;
+                ranges.push(start..i);
+            } else {
+                // A lifetime or label: `'` and its name.
+                ranges.push(start..i + 1);
+                i = ident_end(source, i + 1);
+                ranges.push(start + 1..i);
+            }
+        } else {
+            i += c.len_utf8();
+            ranges.push(start..i);
+        }
+    }
+    Some(ranges)
+}
+
+/// End of a `b'..'`, `b".."`, `c".."` or raw string literal starting at `i`.
+fn prefixed_literal(source: &str, i: usize) -> Option<usize> {
+    let rest = &source[i..];
+    let after_prefix = if rest.starts_with("br") || rest.starts_with("cr") {
+        i + 1
+    } else if rest.starts_with("b'") {
+        return quoted_end(source, i + 2, b'\'');
+    } else if rest.starts_with("b\"") || rest.starts_with("c\"") {
+        return quoted_end(source, i + 2, b'"').map(|end| ident_end(source, end));
+    } else {
+        i
+    };
+    // `r"..."`, `r#"..."#`, but not the raw identifier `r#name`.
+    if !source[after_prefix..].starts_with('r') {
+        return None;
+    }
+    let hashes = source[after_prefix + 1..]
+        .bytes()
+        .take_while(|b| *b == b'#')
+        .count();
+    let open = after_prefix + 1 + hashes;
+    if source.as_bytes().get(open) != Some(&b'"') {
+        return None;
+    }
+    let close = format!("\"{}", "#".repeat(hashes));
+    let end = source[open + 1..].find(&close)? + open + 1 + close.len();
+    Some(ident_end(source, end))
+}
+
+/// Index just past the closing `quote`, with `i` just past the opening one.
+fn quoted_end(source: &str, mut i: usize, quote: u8) -> Option<usize> {
+    let bytes = source.as_bytes();
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Index past the identifier characters (or literal suffix) starting at `i`.
+fn ident_end(source: &str, i: usize) -> usize {
+    source[i..]
+        .char_indices()
+        .find(|(_, c)| !(*c == '_' || c.is_alphanumeric()))
+        .map_or(source.len(), |(n, _)| i + n)
+}
+
+/// Index past the integer or float literal (suffix included) starting at `i`.
+fn number_end(source: &str, i: usize) -> usize {
+    let bytes = source.as_bytes();
+    let at = |i: usize| bytes.get(i).copied().unwrap_or(0);
+    let digits = |mut i: usize| {
+        while at(i).is_ascii_digit() || at(i) == b'_' {
+            i += 1;
+        }
+        i
+    };
+    if at(i) == b'0' && matches!(at(i + 1), b'x' | b'o' | b'b') {
+        return ident_end(source, i + 2);
+    }
+    let mut i = digits(i);
+    // `1.5` and `1.`, but not the range `1..` or the method call `1.max(..)`.
+    if at(i) == b'.' && at(i + 1) != b'.' && !(at(i + 1) == b'_' || at(i + 1).is_ascii_alphabetic())
+    {
+        i = digits(i + 1);
+    }
+    if matches!(at(i), b'e' | b'E') {
+        let sign = usize::from(matches!(at(i + 1), b'+' | b'-'));
+        if at(i + 1 + sign).is_ascii_digit() {
+            i = digits(i + 1 + sign);
+        }
+    }
+    ident_end(source, i)
+}
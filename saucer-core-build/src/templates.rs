@@ -0,0 +1,867 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use syn::{parse_file, Block, Expr, File, Item, Stmt, UseTree};
+
+use crate::ast::{ManagerInfo, TemplateInfo};
+use crate::error::{BuildError, SourceSpan};
+use crate::hygiene::{
+    crate_to_module_name, dependency_infos, read_crate_metadata, read_manifest, DepInfo,
+};
+
+#[derive(Clone)]
+struct UsePath {
+    segments: Vec<String>,
+    is_glob: bool,
+    /// The name, rename or glob as written, to point diagnostics at.
+    tree: UseTree,
+}
+
+fn collect_rs_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    if !dir.exists() {
+        return;
+    }
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_rs_files(&path, out);
+        } else if path.extension().map(|ext| ext == "rs").unwrap_or(false) {
+            out.push(path);
+        }
+    }
+}
+
+fn candidate_runtime_files(manifest_dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let src_dir = manifest_dir.join("src");
+    files.push(src_dir.join("main.rs"));
+    files.push(src_dir.join("lib.rs"));
+    collect_rs_files(&src_dir.join("bin"), &mut files);
+    collect_rs_files(&manifest_dir.join("tests"), &mut files);
+    collect_rs_files(&manifest_dir.join("examples"), &mut files);
+    files.into_iter().filter(|p| p.exists()).collect()
+}
+
+/// Find the one file among the default candidates that contains `marker`,
+/// the `include!` of the generated runtime.
+pub fn find_runtime_host_file(
+    manifest_dir: &Path,
+    marker: &str,
+) -> Result<(PathBuf, String, File), BuildError> {
+    let mut matches = Vec::new();
+    for path in candidate_runtime_files(manifest_dir) {
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+        if source.contains(marker) {
+            matches.push((path, source));
+        }
+    }
+
+    if matches.is_empty() {
+        return Err(BuildError::new(format!(
+            "no file includes the generated runtime; looked in src/main.rs, src/lib.rs, src/bin/*.rs, tests/**/*.rs and examples/*.rs under {}",
+            manifest_dir.display()
+        ))
+        .with_help(format!("add `mod runtime {{ {}; }}`", marker)));
+    }
+    if matches.len() > 1 {
+        let (path, source) = &matches[1];
+        let offset = source.find(marker).unwrap_or(0);
+        return Err(
+            BuildError::new("the generated runtime is included more than once")
+                .in_file(path)
+                .at_span(SourceSpan::from_byte_range(source, offset..offset + marker.len()))
+                .with_help(format!(
+                    "it is also included in {}; give each runtime its own output with `RuntimeBuilder::output`",
+                    matches[0].0.display()
+                )),
+        );
+    }
+
+    let (path, source) = matches.remove(0);
+    let ast = parse_file(&source).map_err(|e| BuildError::parse(&path, &e))?;
+    Ok((path, source, ast))
+}
+
+/// Read a host named in the build script, which must contain `marker`.
+pub fn read_runtime_host_file(
+    path: &Path,
+    marker: &str,
+) -> Result<(PathBuf, String, File), BuildError> {
+    let source = fs::read_to_string(path).map_err(|e| BuildError::io(path, &e))?;
+    if !source.contains(marker) {
+        return Err(
+            BuildError::new("the runtime host does not include the generated runtime")
+                .in_file(path)
+                .with_help(format!("add `mod runtime {{ {}; }}`", marker)),
+        );
+    }
+    let ast = parse_file(&source).map_err(|e| BuildError::parse(path, &e))?;
+    Ok((path.to_path_buf(), source, ast))
+}
+
+fn template_path(base: &Path, segments: &[String]) -> std::path::PathBuf {
+    let mut path = base.to_path_buf();
+    if !segments.is_empty() {
+        for seg in &segments[..segments.len() - 1] {
+            path.push(seg);
+        }
+        path.push(format!("{}.tea.rs", segments.last().unwrap()));
+    }
+    path
+}
+
+fn push_dep_template(
+    dep: &DepInfo,
+    segments: &[String],
+    templates: &mut Vec<TemplateInfo>,
+) -> Result<(), BuildError> {
+    if segments.is_empty() {
+        return Ok(());
+    }
+
+    let module_name = segments.last().unwrap();
+    if !module_name
+        .chars()
+        .next()
+        .map(|c| c.is_lowercase())
+        .unwrap_or(false)
+    {
+        return Ok(());
+    }
+
+    let tea_path = template_path(&dep.path.join("src"), segments);
+    if !tea_path.exists() {
+        return Err(BuildError::new(format!(
+            "template `{}` imported via `use {}::{};` was not found at {}",
+            module_name,
+            dep.module_name,
+            segments.join("::"),
+            tea_path.display()
+        )));
+    }
+
+    let rs_path = dep.path.join("src").join(format!("{}.rs", module_name));
+    if rs_path.exists() {
+        return Err(BuildError::new(format!(
+            "both {}.rs and {}.tea.rs exist in dependency `{}`",
+            module_name, module_name, dep.crate_name
+        ))
+        .with_help("remove the concrete .rs when providing a template"));
+    }
+
+    if !dep.has_templates {
+        return Err(BuildError::new(format!(
+            "template {}.tea.rs found in dependency `{}`, which does not declare templates",
+            module_name, dep.crate_name
+        ))
+        .with_help(
+            "add `has_templates = true` under [package.metadata.saucer] in its Cargo.toml",
+        ));
+    }
+
+    if templates.iter().any(|t| t.path == tea_path) {
+        return Ok(());
+    }
+
+    templates.push(TemplateInfo {
+        module_name: module_name.clone(),
+        crate_name: dep.crate_name.clone(),
+        path: tea_path,
+        is_root: false,
+        used_helpers: Vec::new(),
+        ports: Vec::new(),
+    });
+    Ok(())
+}
+
+fn process_template_use_paths(
+    template_path: &Path,
+    paths: &[UsePath],
+    deps: &HashMap<String, DepInfo>,
+    templates: &mut Vec<TemplateInfo>,
+    errors: &mut Vec<BuildError>,
+) {
+    for path in paths {
+        if path.segments.is_empty() {
+            continue;
+        }
+
+        let crate_name = &path.segments[0];
+        let Some(dep) = deps.get(crate_name) else {
+            // Not a dependency crate; treat as a normal import.
+            continue;
+        };
+
+        if !dep.has_templates {
+            // Dependency doesn't declare templates; treat as a normal import.
+            continue;
+        }
+
+        if path.is_glob {
+            errors.push(
+                BuildError::new(format!(
+                    "glob imports from `{}` hide which templates are used",
+                    dep.crate_name
+                ))
+                .in_file(template_path)
+                .at(&path.tree)
+                .with_help(format!(
+                    "import templates explicitly, e.g. `use {}::some::template;`",
+                    dep.module_name
+                )),
+            );
+            continue;
+        }
+
+        let relative_segments = &path.segments[1..];
+        if relative_segments.is_empty() {
+            errors.push(
+                BuildError::new(format!(
+                    "template import from `{}` must name a template module",
+                    dep.crate_name
+                ))
+                .in_file(template_path)
+                .at(&path.tree)
+                .with_help(format!("e.g. `use {}::foo;`", dep.module_name)),
+            );
+            continue;
+        }
+
+        if let Err(e) = push_dep_template(dep, relative_segments, templates) {
+            errors.push(e.in_file(template_path).at(&path.tree));
+        }
+    }
+}
+
+fn collect_use_paths(tree: &UseTree, prefix: &mut Vec<String>, out: &mut Vec<UsePath>) {
+    match tree {
+        UseTree::Path(path) => {
+            prefix.push(path.ident.to_string());
+            collect_use_paths(&path.tree, prefix, out);
+            prefix.pop();
+        }
+        UseTree::Name(name) => {
+            let mut p = prefix.clone();
+            p.push(name.ident.to_string());
+            out.push(UsePath {
+                segments: p,
+                is_glob: false,
+                tree: tree.clone(),
+            });
+        }
+        UseTree::Rename(rename) => {
+            let mut p = prefix.clone();
+            p.push(rename.ident.to_string());
+            out.push(UsePath {
+                segments: p,
+                is_glob: false,
+                tree: tree.clone(),
+            });
+        }
+        UseTree::Group(group) => {
+            for item in &group.items {
+                collect_use_paths(item, prefix, out);
+            }
+        }
+        UseTree::Glob(_) => {
+            out.push(UsePath {
+                segments: prefix.clone(),
+                is_glob: true,
+                tree: tree.clone(),
+            });
+        }
+    }
+}
+
+pub fn discover_templates_from_template(
+    template: &TemplateInfo,
+    deps: &HashMap<String, DepInfo>,
+    templates: &mut Vec<TemplateInfo>,
+    errors: &mut Vec<BuildError>,
+) {
+    let ast = match read_template(&template.path) {
+        Ok((_, ast)) => ast,
+        Err(e) => {
+            errors.push(e);
+            return;
+        }
+    };
+
+    for item in ast.items {
+        if let Item::Use(use_item) = item {
+            let mut paths = Vec::new();
+            collect_use_paths(&use_item.tree, &mut Vec::new(), &mut paths);
+            process_template_use_paths(&template.path, &paths, deps, templates, errors);
+        }
+    }
+}
+
+/// Read and parse a template, reporting failures against its path.
+pub fn read_template(path: &Path) -> Result<(String, File), BuildError> {
+    let source = fs::read_to_string(path).map_err(|e| BuildError::io(path, &e))?;
+    let ast = parse_file(&source).map_err(|e| BuildError::parse(path, &e))?;
+    Ok((source, ast))
+}
+
+fn collect_template_paths(
+    tree: &UseTree,
+    prefix: &mut Vec<String>,
+    out: &mut Vec<(Vec<String>, UseTree)>,
+) {
+    match tree {
+        UseTree::Name(name) => {
+            let mut path = prefix.clone();
+            path.push(name.ident.to_string());
+            out.push((path, tree.clone()));
+        }
+        UseTree::Rename(rename) => {
+            let mut path = prefix.clone();
+            path.push(rename.ident.to_string());
+            out.push((path, tree.clone()));
+        }
+        UseTree::Group(group) => {
+            for item in &group.items {
+                collect_template_paths(item, prefix, out);
+            }
+        }
+        UseTree::Path(path) => {
+            prefix.push(path.ident.to_string());
+            collect_template_paths(&path.tree, prefix, out);
+            prefix.pop();
+        }
+        UseTree::Glob(_) => {}
+    }
+}
+
+/// The local crate as seen from the file that includes the runtime.
+struct HostContext<'a> {
+    host_path: &'a Path,
+    template_roots: &'a [PathBuf],
+    package_name: &'a str,
+    has_templates_local: bool,
+    deps: &'a [DepInfo],
+}
+
+fn push_template_for_path(
+    path: &[String],
+    ctx: &HostContext,
+    templates: &mut Vec<TemplateInfo>,
+) -> Result<(), BuildError> {
+    if path.is_empty() {
+        return Ok(());
+    }
+
+    let module_name = path.last().unwrap().clone();
+    if !module_name
+        .chars()
+        .next()
+        .map(|c| c.is_lowercase())
+        .unwrap_or(false)
+    {
+        return Ok(());
+    }
+
+    if path.len() < 2 {
+        return Err(BuildError::new(format!(
+            "template import `runtime::{}` is missing its crate namespace",
+            path.join("::")
+        ))
+        .with_help("import templates as `use runtime::<crate>::<template>;`"));
+    }
+
+    let mut crate_seg = path[0].clone();
+    let mut module_segments: &[String] = &path[1..];
+
+    // Allow paths like runtime::sync::mock_app::app by unwrapping the backend namespace.
+    if crate_seg == "sync" || crate_seg == "async" {
+        if module_segments.is_empty() {
+            return Ok(());
+        }
+        crate_seg = module_segments[0].clone();
+        module_segments = &module_segments[1..];
+    }
+    let package_module = crate_to_module_name(ctx.package_name);
+    let package_name = ctx.package_name;
+
+    // Local crate namespace
+    if crate_seg == package_module {
+        let searched: Vec<PathBuf> = ctx
+            .template_roots
+            .iter()
+            .map(|root| template_path(root, module_segments))
+            .collect();
+        let Some(found) = searched.iter().position(|path| path.exists()) else {
+            let searched: Vec<String> = searched.iter().map(|p| p.display().to_string()).collect();
+            return Err(BuildError::new(format!(
+                "template {}.tea.rs not found locally in `{}` at {}",
+                module_segments.last().unwrap(),
+                package_name,
+                searched.join(" or ")
+            )));
+        };
+        let (root, local_path) = (&ctx.template_roots[found], searched[found].clone());
+        if !ctx.has_templates_local {
+            return Err(BuildError::new(format!(
+                "template {}.tea.rs found in crate `{}`, which does not declare templates",
+                module_segments.last().unwrap(),
+                package_name
+            ))
+            .with_help(
+                "add `has_templates = true` under [package.metadata.saucer] in Cargo.toml",
+            ));
+        }
+        let local_rs = root.join(format!("{}.rs", module_segments.last().unwrap()));
+        if local_rs.exists() {
+            return Err(BuildError::new(format!(
+                "both {}.rs and {}.tea.rs exist in crate `{}`",
+                module_segments.last().unwrap(),
+                module_segments.last().unwrap(),
+                package_name
+            ))
+            .with_help("remove the concrete .rs when providing a template"));
+        }
+        if templates.iter().any(|t| t.path == local_path) {
+            return Ok(());
+        }
+        templates.push(TemplateInfo {
+            module_name: module_segments.last().unwrap().clone(),
+            crate_name: package_name.to_string(),
+            path: local_path,
+            is_root: true,
+            used_helpers: Vec::new(),
+            ports: Vec::new(),
+        });
+        return Ok(());
+    }
+
+    // Dependency crate namespace (explicit crate required)
+    let mut candidates: Vec<(&DepInfo, std::path::PathBuf)> = Vec::new();
+    for dep in ctx.deps {
+        if dep.module_name != crate_seg {
+            continue;
+        }
+
+        let dep_template_path = template_path(&dep.path.join("src"), module_segments);
+        if dep_template_path.exists() {
+            candidates.push((dep, dep_template_path));
+        }
+    }
+
+    match candidates.len() {
+        0 => Err(BuildError::new(format!(
+            "template {}.tea.rs not found in dependency `{}` (or locally)",
+            module_segments.last().unwrap(),
+            crate_seg
+        ))),
+        1 => {
+            let (dep, dep_path) = candidates.into_iter().next().unwrap();
+            if !dep.has_templates {
+                return Err(BuildError::new(format!(
+                    "template {}.tea.rs found in dependency `{}`, which does not declare templates",
+                    module_segments.last().unwrap(),
+                    dep.crate_name
+                ))
+                .with_help(
+                    "add `has_templates = true` under [package.metadata.saucer] in its Cargo.toml",
+                ));
+            }
+            let rs_path = dep
+                .path
+                .join("src")
+                .join(format!("{}.rs", module_segments.last().unwrap()));
+            if rs_path.exists() {
+                return Err(BuildError::new(format!(
+                    "both {}.rs and {}.tea.rs exist in dependency `{}`",
+                    module_segments.last().unwrap(),
+                    module_segments.last().unwrap(),
+                    dep.crate_name
+                ))
+                .with_help("remove the concrete .rs when providing a template"));
+            }
+            if templates.iter().any(|t| t.path == dep_path) {
+                return Ok(());
+            }
+            templates.push(TemplateInfo {
+                module_name: module_segments.last().unwrap().clone(),
+                crate_name: dep.crate_name.clone(),
+                path: dep_path,
+                is_root: true,
+                used_helpers: Vec::new(),
+                ports: Vec::new(),
+            });
+            Ok(())
+        }
+        _ => {
+            let names: Vec<String> = candidates
+                .iter()
+                .map(|(d, _)| d.crate_name.clone())
+                .collect();
+            Err(BuildError::new(format!(
+                "template {}.tea.rs found in multiple dependencies: {}",
+                module_segments.last().unwrap(),
+                names.join(", ")
+            ))
+            .with_help(format!(
+                "use an explicit crate hint, e.g. `use runtime::crate_name::{};`",
+                module_segments.last().unwrap()
+            )))
+        }
+    }
+}
+
+fn extract_modules_from_tree(
+    tree: &UseTree,
+    ctx: &HostContext,
+    templates: &mut Vec<TemplateInfo>,
+    errors: &mut Vec<BuildError>,
+) {
+    let mut paths: Vec<(Vec<String>, UseTree)> = Vec::new();
+    collect_template_paths(tree, &mut Vec::new(), &mut paths);
+
+    for (path, tree) in paths {
+        if let Err(e) = push_template_for_path(&path, ctx, templates) {
+            errors.push(e.in_file(ctx.host_path).at(&tree));
+        }
+    }
+}
+
+fn extract_runtime_modules(
+    tree: &UseTree,
+    ctx: &HostContext,
+    templates: &mut Vec<TemplateInfo>,
+    errors: &mut Vec<BuildError>,
+) {
+    match tree {
+        UseTree::Path(path) => {
+            if path.ident == "runtime" {
+                // Found `use runtime::xxx` - extract module names
+                extract_modules_from_tree(&path.tree, ctx, templates, errors);
+            } else {
+                // Recurse to handle paths like crate::runtime::app
+                extract_runtime_modules(&path.tree, ctx, templates, errors);
+            }
+        }
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                extract_runtime_modules(tree, ctx, templates, errors);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Discover templates from the file that includes the generated runtime by looking for
+/// `use runtime::xxx` statements (local or dependency crates). The crate's own
+/// templates are looked up in `template_roots`, in order.
+pub fn discover_templates(
+    runtime_host_path: &Path,
+    runtime_host_ast: &File,
+    cargo_toml: &Path,
+    template_roots: &[PathBuf],
+    errors: &mut Vec<BuildError>,
+) -> Vec<TemplateInfo> {
+    let mut templates = Vec::new();
+    let Some((deps, has_templates_local, package_name)) = read_local_manifest(cargo_toml, errors)
+    else {
+        return templates;
+    };
+    let ctx = HostContext {
+        host_path: runtime_host_path,
+        template_roots,
+        package_name: &package_name,
+        has_templates_local,
+        deps: &deps,
+    };
+
+    for item in &runtime_host_ast.items {
+        if let Item::Use(use_item) = item {
+            extract_runtime_modules(&use_item.tree, &ctx, &mut templates, errors);
+        }
+    }
+
+    templates
+}
+
+/// Discover the root templates a build script named (`app`, `screens::home`)
+/// among the crate's own templates. Problems are reported against its Cargo.toml.
+pub fn discover_named_templates(
+    cargo_toml: &Path,
+    apps: &[String],
+    template_roots: &[PathBuf],
+    errors: &mut Vec<BuildError>,
+) -> Vec<TemplateInfo> {
+    let mut templates = Vec::new();
+    let Some((deps, has_templates_local, package_name)) = read_local_manifest(cargo_toml, errors)
+    else {
+        return templates;
+    };
+    let ctx = HostContext {
+        host_path: cargo_toml,
+        template_roots,
+        package_name: &package_name,
+        has_templates_local,
+        deps: &deps,
+    };
+
+    for app in apps {
+        let path: Vec<String> = std::iter::once(crate_to_module_name(&package_name))
+            .chain(app.split("::").map(|seg| seg.trim().to_string()))
+            .collect();
+        if let Err(e) = push_template_for_path(&path, &ctx, &mut templates) {
+            errors.push(e.in_file(cargo_toml));
+        }
+    }
+
+    templates
+}
+
+/// Dependencies, `has_templates` and package name of the local crate.
+fn read_local_manifest(
+    cargo_toml: &Path,
+    errors: &mut Vec<BuildError>,
+) -> Option<(Vec<DepInfo>, bool, String)> {
+    let manifest = dependency_infos(cargo_toml).and_then(|deps| {
+        Ok((
+            deps,
+            read_crate_metadata(cargo_toml)?,
+            package_name(cargo_toml)?,
+        ))
+    });
+    match manifest {
+        Ok(manifest) => Some(manifest),
+        Err(e) => {
+            errors.push(e);
+            None
+        }
+    }
+}
+
+/// Find which effect manager the reconciler passed to `Runtime::new`/`run`
+/// comes from, looking in the runtime host first and then, with
+/// `search_candidates`, the other files that could run it. A host the build
+/// script named is the only file searched, so another runtime in the same
+/// crate cannot lend it a reconciler.
+pub fn find_reconciler_manager_module(
+    manifest_dir: &Path,
+    runtime_host_ast: &File,
+    search_candidates: bool,
+    managers: &[ManagerInfo],
+) -> Option<String> {
+    if find_runtime_run_call_any(runtime_host_ast).is_some() || !search_candidates {
+        return reconciler_manager_in(runtime_host_ast, managers);
+    }
+    for path in candidate_runtime_files(manifest_dir) {
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let Ok(ast) = parse_file(&source) else {
+            continue;
+        };
+        if let Some(module) = reconciler_manager_in(&ast, managers) {
+            return Some(module);
+        }
+    }
+    None
+}
+
+fn reconciler_manager_in(ast: &File, managers: &[ManagerInfo]) -> Option<String> {
+    let use_aliases = collect_use_aliases(ast);
+    let runtime_call = find_runtime_run_call_any(ast)?;
+    let reconciler_arg = runtime_call.args.iter().nth(4)?;
+    let segments = extract_signature_path(reconciler_arg)?;
+    let crate_name = resolve_crate_from_path(&segments, &use_aliases)?;
+    managers
+        .iter()
+        .find(|m| m.module_name == crate_name)
+        .map(|m| m.module_name.clone())
+}
+
+fn collect_use_aliases(ast: &File) -> HashMap<String, Vec<String>> {
+    let mut aliases = HashMap::new();
+    for item in &ast.items {
+        if let Item::Use(use_item) = item {
+            collect_alias_from_tree(&use_item.tree, &mut Vec::new(), &mut aliases);
+        }
+    }
+    aliases
+}
+
+fn collect_alias_from_tree(
+    tree: &UseTree,
+    prefix: &mut Vec<String>,
+    aliases: &mut HashMap<String, Vec<String>>,
+) {
+    match tree {
+        UseTree::Path(path) => {
+            prefix.push(path.ident.to_string());
+            collect_alias_from_tree(&path.tree, prefix, aliases);
+            prefix.pop();
+        }
+        UseTree::Name(name) => {
+            aliases.insert(name.ident.to_string(), prefix.clone());
+        }
+        UseTree::Rename(rename) => {
+            aliases.insert(rename.rename.to_string(), prefix.clone());
+        }
+        UseTree::Group(group) => {
+            for item in &group.items {
+                collect_alias_from_tree(item, prefix, aliases);
+            }
+        }
+        UseTree::Glob(_) => {}
+    }
+}
+
+fn find_runtime_run_call_any(ast: &File) -> Option<&syn::ExprCall> {
+    for item in &ast.items {
+        if let Item::Fn(func) = item {
+            if let Some(call) = find_runtime_call_in_block(&func.block) {
+                return Some(call);
+            }
+        }
+    }
+    None
+}
+
+fn find_runtime_call_in_block(block: &Block) -> Option<&syn::ExprCall> {
+    for stmt in &block.stmts {
+        if let Some(call) = find_runtime_call_in_stmt(stmt) {
+            return Some(call);
+        }
+    }
+    None
+}
+
+fn find_runtime_call_in_stmt(stmt: &Stmt) -> Option<&syn::ExprCall> {
+    match stmt {
+        Stmt::Expr(expr, _) => find_runtime_call_in_expr(expr),
+        Stmt::Local(local) => local
+            .init
+            .as_ref()
+            .and_then(|init| find_runtime_call_in_expr(&init.expr)),
+        _ => None,
+    }
+}
+
+fn find_runtime_call_in_expr(expr: &Expr) -> Option<&syn::ExprCall> {
+    if let Expr::Call(call) = expr {
+        if is_runtime_ctor_or_run(&call.func) {
+            return Some(call);
+        }
+    }
+
+    match expr {
+        Expr::Block(block) => find_runtime_call_in_block(&block.block),
+        Expr::Paren(paren) => find_runtime_call_in_expr(&paren.expr),
+        Expr::Await(await_expr) => find_runtime_call_in_expr(&await_expr.base),
+        Expr::MethodCall(mc) => {
+            // `Runtime::new(...).run()` finds the constructor in the receiver.
+            for arg in &mc.args {
+                if let Some(call) = find_runtime_call_in_expr(arg) {
+                    return Some(call);
+                }
+            }
+            find_runtime_call_in_expr(&mc.receiver)
+        }
+        Expr::If(e) => {
+            if let Some(call) = find_runtime_call_in_expr(&e.cond) {
+                return Some(call);
+            }
+            if let Some(call) = find_runtime_call_in_block(&e.then_branch) {
+                return Some(call);
+            }
+            if let Some((_, else_expr)) = &e.else_branch {
+                return find_runtime_call_in_expr(else_expr);
+            }
+            None
+        }
+        Expr::Match(m) => {
+            if let Some(call) = find_runtime_call_in_expr(&m.expr) {
+                return Some(call);
+            }
+            for arm in &m.arms {
+                if let Some(call) = find_runtime_call_in_expr(&arm.body) {
+                    return Some(call);
+                }
+            }
+            None
+        }
+        Expr::Let(le) => find_runtime_call_in_expr(&le.expr),
+        Expr::Tuple(t) => t.elems.iter().find_map(find_runtime_call_in_expr),
+        Expr::Array(a) => a.elems.iter().find_map(find_runtime_call_in_expr),
+        _ => None,
+    }
+}
+
+fn is_runtime_ctor_or_run(func: &Expr) -> bool {
+    if let Expr::Path(path) = func {
+        let segments = path
+            .path
+            .segments
+            .iter()
+            .map(|seg| seg.ident.to_string())
+            .collect::<Vec<_>>();
+        if segments.len() >= 2 && matches!(segments.last().map(String::as_str), Some("run" | "new"))
+        {
+            if segments[segments.len() - 2] == "Runtime" {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn extract_signature_path(expr: &Expr) -> Option<Vec<String>> {
+    match expr {
+        Expr::Call(call) => extract_signature_path(&call.func),
+        Expr::Path(path) => Some(
+            path.path
+                .segments
+                .iter()
+                .map(|seg| seg.ident.to_string())
+                .collect(),
+        ),
+        Expr::Paren(paren) => extract_signature_path(&paren.expr),
+        _ => None,
+    }
+}
+
+fn resolve_crate_from_path(
+    segments: &[String],
+    aliases: &HashMap<String, Vec<String>>,
+) -> Option<String> {
+    if segments.is_empty() {
+        return None;
+    }
+
+    if segments.len() == 1 {
+        aliases
+            .get(&segments[0])
+            .and_then(|path| path.first().cloned())
+    } else {
+        let first = &segments[0];
+        if let Some(path) = aliases.get(first) {
+            path.first().cloned()
+        } else {
+            Some(first.clone())
+        }
+    }
+}
+
+// ===== Helper utilities used outside the module =====
+
+pub fn package_name(cargo_toml_path: &Path) -> Result<String, BuildError> {
+    let toml = read_manifest(cargo_toml_path)?;
+    Ok(toml
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .unwrap_or("app")
+        .to_string())
+}
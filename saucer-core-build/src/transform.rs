@@ -0,0 +1,606 @@
+use std::path::Path;
+
+use proc_macro2::{Ident, Span};
+use quote::ToTokens;
+use syn::visit::Visit;
+use syn::{File, Item, ItemUse, ReturnType, UseTree};
+
+use crate::ast::{ManagerInfo, PortDirection, PortSpec};
+use crate::error::{BuildError, SourceSpan};
+use crate::source_map::SourceMap;
+use crate::templates::read_template;
+
+/// A .tea.rs template with its fictional imports rewritten to the generated runtime.
+pub struct TemplateTransform {
+    pub code: String,
+    pub used_helpers: Vec<(String, String)>,
+}
+
+/// One name brought in by a `use` tree, with the full path leading to it.
+struct UseLeaf {
+    segments: Vec<Ident>,
+    kind: LeafKind,
+    /// The name, rename or glob as written, to point diagnostics at.
+    tree: UseTree,
+}
+
+enum LeafKind {
+    Name,
+    Rename(Ident),
+    Glob,
+}
+
+enum Rewrite {
+    Keep,
+    Drop,
+    Replace {
+        segments: Vec<Ident>,
+        /// `Cmd`/`Sub` are imported whether or not the template uses both.
+        allow_unused: bool,
+        helper: Option<(String, String)>,
+    },
+}
+
+/// Transform a .tea.rs template by rewriting fictional imports.
+///
+/// Decisions are made on the `syn` AST, so grouped, nested, renamed, `pub` and
+/// multi-line imports all work. Edits are spliced back into the original
+/// source where their tokens are found in it, leaving every other line where
+/// the developer wrote it.
+///
+/// Problems are reported to `errors`; the returned code is only meaningful
+/// when none were.
+pub fn transform_template(
+    template_path: &Path,
+    managers: &[ManagerInfo],
+    dep_templates: &[(String, String)],
+    template_ports: &[PortSpec],
+    errors: &mut Vec<BuildError>,
+) -> TemplateTransform {
+    let (source, file) = match read_template(template_path) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            errors.push(e);
+            return TemplateTransform {
+                code: String::new(),
+                used_helpers: Vec::new(),
+            };
+        }
+    };
+    let unmapped = |errors: &mut Vec<BuildError>| {
+        errors.push(
+            BuildError::new("could not match the template's tokens back to its source")
+                .in_file(template_path),
+        );
+        TemplateTransform {
+            code: String::new(),
+            used_helpers: Vec::new(),
+        }
+    };
+    // Spans only carry template positions outside the `runtime!` macro, so
+    // edits are placed by finding each node's tokens in the source instead.
+    let Some(map) = SourceMap::new(&source) else {
+        return unmapped(errors);
+    };
+    let mut edits: Vec<(std::ops::Range<usize>, String)> = Vec::new();
+
+    // Included files cannot carry inner attributes (`//!` docs included).
+    let mut cursor = 0;
+    for attr in &file.attrs {
+        let Some(tokens) = map.find(attr, cursor) else {
+            return unmapped(errors);
+        };
+        cursor = tokens.end;
+        let range = map.bytes(tokens);
+        edits.push((range.clone(), newlines_of(&source[range])));
+    }
+
+    // Drop outgoing #[port] functions (so generated helpers win) and strip the
+    // attribute from incoming ones.
+    if port_edits(&file, template_ports, &map, &source, &mut edits).is_none() {
+        return unmapped(errors);
+    }
+
+    let mut uses = UseCollector::default();
+    uses.visit_file(&file);
+
+    let mut used_helpers = Vec::new();
+    let mut cursor = 0;
+    for item in uses.items {
+        let Some(tokens) = map.find(&item, cursor) else {
+            return unmapped(errors);
+        };
+        cursor = tokens.end;
+        let range = map.bytes(tokens.clone());
+        if edits
+            .iter()
+            .any(|(r, _)| r.start <= range.start && range.end <= r.end)
+        {
+            // Inside a removed outgoing port.
+            continue;
+        }
+
+        let mut leaves = Vec::new();
+        collect_leaves(&item.tree, &mut Vec::new(), &mut leaves);
+        let mut rewrites = Vec::new();
+        for leaf in leaves {
+            match rewrite_leaf(&leaf, managers, dep_templates) {
+                Ok(rewrite) => rewrites.push((leaf, rewrite)),
+                Err(e) => {
+                    let mut e = e.in_file(template_path);
+                    if let Some(leaf_tokens) = map.find(&leaf.tree, tokens.start) {
+                        let bytes = map.bytes(leaf_tokens);
+                        e = e.at_span(SourceSpan::from_byte_range(&source, bytes));
+                    }
+                    errors.push(e);
+                }
+            }
+        }
+        if rewrites.iter().all(|(_, r)| matches!(r, Rewrite::Keep)) {
+            continue;
+        }
+
+        // Split the import into one `use` per name so each can be rewritten on its own.
+        let mut replacement = Vec::new();
+        for (leaf, rewrite) in rewrites {
+            let (segments, leading_colon, allow_unused) = match rewrite {
+                Rewrite::Drop => continue,
+                Rewrite::Keep => (leaf.segments, item.leading_colon, false),
+                Rewrite::Replace {
+                    segments,
+                    allow_unused,
+                    helper,
+                } => {
+                    used_helpers.extend(helper);
+                    (segments, None, allow_unused)
+                }
+            };
+            let mut split = item.clone();
+            split.leading_colon = leading_colon;
+            split.tree = leaf_tree(&segments, &leaf.kind);
+            if allow_unused && !allows_unused_imports(&item.attrs) {
+                split
+                    .attrs
+                    .push(syn::parse_quote!(#[allow(unused_imports)]));
+            }
+            replacement.push(split.to_token_stream().to_string());
+        }
+        let original = &source[range.clone()];
+        edits.push((range, replacement.join(" ") + &newlines_of(original)));
+    }
+
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+    let mut code = source;
+    for (range, text) in edits {
+        code.replace_range(range, &text);
+    }
+
+    TemplateTransform { code, used_helpers }
+}
+
+/// `None` when an item could not be found in the source.
+fn port_edits(
+    file: &File,
+    template_ports: &[PortSpec],
+    map: &SourceMap,
+    source: &str,
+    edits: &mut Vec<(std::ops::Range<usize>, String)>,
+) -> Option<()> {
+    let mut cursor = 0;
+    for item in &file.items {
+        let item_tokens = map.find(item, cursor)?;
+        cursor = item_tokens.end;
+        let Item::Fn(func) = item else {
+            continue;
+        };
+        let Some(port_attr) = func.attrs.iter().find(|a| a.path().is_ident("port")) else {
+            continue;
+        };
+        let name = func.sig.ident.to_string();
+        let direction = template_ports.iter().find_map(|p| match p {
+            PortSpec::Incoming(p) if p.name == name => Some(PortDirection::Incoming),
+            PortSpec::Outgoing(p) if p.name == name => Some(PortDirection::Outgoing),
+            _ => None,
+        });
+        // Ports that failed to parse were already reported.
+        let Some(direction) = direction else {
+            continue;
+        };
+
+        match direction {
+            PortDirection::Outgoing => {
+                let range = map.bytes(item_tokens);
+                edits.push((range.clone(), newlines_of(&source[range])));
+            }
+            PortDirection::Incoming => {
+                // Incoming: return Msg so Sub is stripped from generated code.
+                let range = map.bytes(map.find(port_attr, item_tokens.start)?);
+                edits.push((range.clone(), newlines_of(&source[range])));
+                if let ReturnType::Type(_, ty) = &func.sig.output {
+                    // The first `-> Type` after the name, past the `->`.
+                    let name = map.find(&func.sig.ident, item_tokens.start)?;
+                    let output = map.find(&func.sig.output, name.end)?;
+                    let ty = map.find(ty, output.start)?;
+                    edits.push((map.bytes(ty), "Msg".to_string()));
+                }
+            }
+        }
+    }
+    Some(())
+}
+
+fn rewrite_leaf(
+    leaf: &UseLeaf,
+    managers: &[ManagerInfo],
+    dep_templates: &[(String, String)],
+) -> Result<Rewrite, BuildError> {
+    let names: Vec<String> = leaf.segments.iter().map(|s| s.to_string()).collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    let is_glob = matches!(leaf.kind, LeafKind::Glob);
+    let ident = |name: &str| Ident::new(name, Span::call_site());
+    let runtime_path = |rest: &[&str]| -> Vec<Ident> {
+        ["super", "super"]
+            .iter()
+            .chain(rest)
+            .map(|name| ident(name))
+            .collect()
+    };
+
+    // Hygiene: block direct imports of core plumbing that would bypass helper permissions.
+    if let ["saucer_core", "CoreCmd" | "CoreRequest", ..] = names[..] {
+        return Err(
+            BuildError::new("template imports saucer_core::CoreCmd/CoreRequest directly")
+                .with_help("use fictional imports (saucer_core::Cmd, <manager>::command::helper) so tooling and permissions stay enforced"),
+        );
+    }
+    let anchor = names
+        .iter()
+        .take_while(|n| **n == "runtime" || **n == "super")
+        .count();
+    if anchor > 0 && names.get(anchor) == Some(&"Request") {
+        return Err(
+            BuildError::new("template imports the generated Request type directly").with_help(
+                "construct commands via the generated helpers instead of Request/Request::map",
+            ),
+        );
+    }
+    // Optional hygiene: block direct imports of manager Request types to force helper usage.
+    if !is_glob
+        && managers.iter().any(|m| {
+            names.len() > 1 && names[0] == m.module_name && names.last() == Some(&&*m.request_type)
+        })
+    {
+        return Err(
+            BuildError::new("template imports an effect manager Request type directly").with_help(
+                "use fictional ::command:: helpers instead so permissions and tooling apply",
+            ),
+        );
+    }
+
+    match (&names[..], is_glob) {
+        // Template-only port markers.
+        (["saucer_core", "port", ..], _) => return Ok(Rewrite::Drop),
+        ([] | ["saucer_core"], _) => return Ok(Rewrite::Keep),
+        (["saucer_core", name @ ("Cmd" | "Sub")], false) => {
+            return Ok(Rewrite::Replace {
+                segments: runtime_path(&[name]),
+                allow_unused: true,
+                helper: None,
+            })
+        }
+        (["saucer_core", "command", helper], false) => {
+            return Ok(Rewrite::Replace {
+                segments: runtime_path(&["core", helper]),
+                allow_unused: false,
+                helper: Some(("saucer_core".to_string(), helper.to_string())),
+            })
+        }
+        ([krate, "command", helper], false) if managers.iter().any(|m| m.module_name == *krate) => {
+            return Ok(Rewrite::Replace {
+                segments: runtime_path(&[krate, helper]),
+                allow_unused: false,
+                helper: Some((krate.to_string(), helper.to_string())),
+            })
+        }
+        _ => {}
+    }
+
+    if names[1..].contains(&"command") {
+        let help = if is_glob {
+            "import helpers by name so only the ones used are generated"
+        } else {
+            "ensure the crate is registered as an effect manager and exposes the helper in its requests module"
+        };
+        return Err(BuildError::new(format!(
+            "unrecognized fictional import `{}{}`",
+            names.join("::"),
+            if is_glob { "::*" } else { "" }
+        ))
+        .with_help(help));
+    }
+
+    // Cross-crate templates resolve to their module in the generated runtime.
+    let krate = names[0];
+    let module = names[names.len() - 1];
+    if !is_glob && names.len() >= 2 && dep_templates.iter().any(|(c, m)| c == krate && m == module)
+    {
+        return Ok(Rewrite::Replace {
+            segments: runtime_path(&names),
+            allow_unused: false,
+            helper: None,
+        });
+    }
+
+    Ok(Rewrite::Keep)
+}
+
+#[derive(Default)]
+struct UseCollector {
+    items: Vec<ItemUse>,
+}
+
+impl<'ast> Visit<'ast> for UseCollector {
+    fn visit_item_use(&mut self, item: &'ast ItemUse) {
+        self.items.push(item.clone());
+    }
+}
+
+fn collect_leaves(tree: &UseTree, prefix: &mut Vec<Ident>, out: &mut Vec<UseLeaf>) {
+    match tree {
+        UseTree::Path(path) => {
+            prefix.push(path.ident.clone());
+            collect_leaves(&path.tree, prefix, out);
+            prefix.pop();
+        }
+        // `{self}` names the group's own path; `prefix::self` is not a valid import.
+        UseTree::Name(name) if name.ident == "self" && !prefix.is_empty() => out.push(UseLeaf {
+            segments: prefix.clone(),
+            kind: LeafKind::Name,
+            tree: tree.clone(),
+        }),
+        UseTree::Rename(rename) if rename.ident == "self" && !prefix.is_empty() => {
+            out.push(UseLeaf {
+                segments: prefix.clone(),
+                kind: LeafKind::Rename(rename.rename.clone()),
+                tree: tree.clone(),
+            })
+        }
+        UseTree::Name(name) => {
+            let mut segments = prefix.clone();
+            segments.push(name.ident.clone());
+            out.push(UseLeaf {
+                segments,
+                kind: LeafKind::Name,
+                tree: tree.clone(),
+            });
+        }
+        UseTree::Rename(rename) => {
+            let mut segments = prefix.clone();
+            segments.push(rename.ident.clone());
+            out.push(UseLeaf {
+                segments,
+                kind: LeafKind::Rename(rename.rename.clone()),
+                tree: tree.clone(),
+            });
+        }
+        UseTree::Glob(_) => out.push(UseLeaf {
+            segments: prefix.clone(),
+            kind: LeafKind::Glob,
+            tree: tree.clone(),
+        }),
+        UseTree::Group(group) => {
+            for item in &group.items {
+                collect_leaves(item, prefix, out);
+            }
+        }
+    }
+}
+
+/// Whether the import already carries `#[allow(unused_imports, ..)]`, so
+/// split leaves don't repeat it.
+fn allows_unused_imports(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("allow")
+            && attr
+                .parse_args_with(
+                    syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+                )
+                .is_ok_and(|lints| lints.iter().any(|lint| lint.is_ident("unused_imports")))
+    })
+}
+
+/// Rebuild a single-name `use` tree; for globs every segment is a path segment.
+fn leaf_tree(segments: &[Ident], kind: &LeafKind) -> UseTree {
+    let (mut tree, path) = match kind {
+        LeafKind::Glob => (
+            UseTree::Glob(syn::UseGlob {
+                star_token: Default::default(),
+            }),
+            segments,
+        ),
+        LeafKind::Name | LeafKind::Rename(_) => {
+            let (last, path) = segments.split_last().expect("use leaf has a name");
+            let tree = match kind {
+                LeafKind::Rename(alias) => UseTree::Rename(syn::UseRename {
+                    ident: last.clone(),
+                    as_token: Default::default(),
+                    rename: alias.clone(),
+                }),
+                _ => UseTree::Name(syn::UseName {
+                    ident: last.clone(),
+                }),
+            };
+            (tree, path)
+        }
+    };
+    for segment in path.iter().rev() {
+        tree = UseTree::Path(syn::UsePath {
+            ident: segment.clone(),
+            colon2_token: Default::default(),
+            tree: Box::new(tree),
+        });
+    }
+    tree
+}
+
+/// Only the line breaks of `text`, so replacing it keeps later lines in place.
+fn newlines_of(text: &str) -> String {
+    text.chars().filter(|c| *c == '\n').collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time_manager() -> ManagerInfo {
+        ManagerInfo {
+            crate_name: "saucer-time-manager".to_string(),
+            module_name: "saucer_time_manager".to_string(),
+            variant: "Time".to_string(),
+            request_type: "TimeRequest".to_string(),
+            subscription_type: Some("TimeSub".to_string()),
+            manager_type: "TimeManager".to_string(),
+            self_msg_type: "()".to_string(),
+            spawn: false,
+            lib_path: "saucer-time-manager/src/lib.rs".into(),
+            manifest_path: "saucer-time-manager/Cargo.toml".into(),
+        }
+    }
+
+    /// Transform `source` as a template next to a time manager and a
+    /// `mock_widget::widget` template, returning its code or error messages.
+    fn transform(name: &str, source: &str) -> Result<String, Vec<String>> {
+        let dir =
+            std::env::temp_dir().join(format!("saucer-transform-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("app.tea.rs");
+        std::fs::write(&path, source).unwrap();
+        let mut errors = Vec::new();
+        let transformed = transform_template(
+            &path,
+            &[time_manager()],
+            &[("mock_widget".to_string(), "widget".to_string())],
+            &[],
+            &mut errors,
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+        if errors.is_empty() {
+            Ok(transformed.code)
+        } else {
+            Err(errors.iter().map(|e| e.message.clone()).collect())
+        }
+    }
+
+    fn leaves(source: &str) -> Vec<String> {
+        let item: ItemUse = syn::parse_str(source).unwrap();
+        let mut leaves = Vec::new();
+        collect_leaves(&item.tree, &mut Vec::new(), &mut leaves);
+        leaves
+            .iter()
+            .map(|leaf| {
+                leaf_tree(&leaf.segments, &leaf.kind)
+                    .to_token_stream()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nested_groups_split_into_one_leaf_per_name() {
+        assert_eq!(
+            leaves("use a::{b::{c, d}, e};"),
+            ["a :: b :: c", "a :: b :: d", "a :: e"]
+        );
+
+        let code = transform(
+            "nested",
+            "use saucer_time_manager::{command::{every, notify_after}, Posix};\n",
+        )
+        .unwrap();
+        assert_eq!(
+            code,
+            "use super :: super :: saucer_time_manager :: every ; \
+             use super :: super :: saucer_time_manager :: notify_after ; \
+             use saucer_time_manager :: Posix ;\n"
+        );
+    }
+
+    #[test]
+    fn renames_keep_their_alias() {
+        assert_eq!(leaves("use a::{b as c, d};"), ["a :: b as c", "a :: d"]);
+
+        let code = transform(
+            "rename",
+            "use saucer_time_manager::command::every as tick;\n",
+        )
+        .unwrap();
+        assert_eq!(
+            code,
+            "use super :: super :: saucer_time_manager :: every as tick ;\n"
+        );
+    }
+
+    #[test]
+    fn self_in_a_group_names_the_group_path() {
+        assert_eq!(
+            leaves("use a::b::{self, c, self as d};"),
+            ["a :: b", "a :: b :: c", "a :: b as d"]
+        );
+
+        let code = transform("self", "use mock_widget::{self, widget::{self as w}};\n").unwrap();
+        assert_eq!(
+            code,
+            "use mock_widget ; use super :: super :: mock_widget :: widget as w ;\n"
+        );
+    }
+
+    #[test]
+    fn globs_are_kept_unless_they_name_helpers() {
+        assert_eq!(leaves("use a::{b::*, *};"), ["a :: b :: *", "a :: *"]);
+
+        let source = "use saucer_time_manager::*;\n";
+        assert_eq!(transform("glob", source).unwrap(), source);
+
+        let errors =
+            transform("glob-helpers", "use saucer_time_manager::command::*;\n").unwrap_err();
+        assert_eq!(
+            errors,
+            ["unrecognized fictional import `saucer_time_manager::command::*`"]
+        );
+    }
+
+    #[test]
+    fn visibility_is_kept_on_every_split_import() {
+        let code = transform(
+            "visibility",
+            "pub use mock_widget::widget;\npub(crate) use saucer_core::{command::shutdown, Sub};\n",
+        )
+        .unwrap();
+        assert_eq!(
+            code,
+            "pub use super :: super :: mock_widget :: widget ;\n\
+             pub (crate) use super :: super :: core :: shutdown ; \
+             # [allow (unused_imports)] pub (crate) use super :: super :: Sub ;\n"
+        );
+    }
+
+    #[test]
+    fn existing_attributes_are_copied_without_repeating_allow() {
+        let code = transform(
+            "attributes",
+            "#[allow(unused_imports)]\n#[cfg(test)]\nuse saucer_core::{Cmd, Sub};\n",
+        )
+        .unwrap();
+        assert_eq!(
+            code,
+            "# [allow (unused_imports)] # [cfg (test)] use super :: super :: Cmd ; \
+             # [allow (unused_imports)] # [cfg (test)] use super :: super :: Sub ;\n\n\n"
+        );
+
+        let code = transform("cfg", "#[cfg(test)]\nuse saucer_core::Cmd;\n").unwrap();
+        assert_eq!(
+            code,
+            "# [cfg (test)] # [allow (unused_imports)] use super :: super :: Cmd ;\n\n"
+        );
+    }
+}
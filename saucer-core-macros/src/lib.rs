@@ -1,3 +1,6 @@
+#[cfg(feature = "runtime")]
+mod runtime;
+
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 use syn::{parse_macro_input, Data, DeriveInput, Fields};
@@ -10,9 +13,17 @@ pub fn derive_request(input: TokenStream) -> TokenStream {
 
     let body = match input.data {
         Data::Struct(ref data) => {
-            struct_debug(&name, (impl_generics, ty_generics, where_clause), data)
+            let debug = struct_debug(&name, (impl_generics, ty_generics, where_clause), data);
+            let fields = struct_fields(&name, &input.generics, data);
+            let resolve = struct_resolve(&name, &input.generics, data);
+            quote! { #debug #fields #resolve }
+        }
+        Data::Enum(ref data) => {
+            let debug = enum_debug(&name, (impl_generics, ty_generics, where_clause), data);
+            let fields = enum_fields(&name, &input.generics, data);
+            let resolve = enum_resolve(&name, &input.generics, data);
+            quote! { #debug #fields #resolve }
         }
-        Data::Enum(ref data) => enum_debug(&name, (impl_generics, ty_generics, where_clause), data),
         Data::Union(_) => {
             return syn::Error::new_spanned(name, "Request derive does not support unions")
                 .to_compile_error()
@@ -23,6 +34,63 @@ pub fn derive_request(input: TokenStream) -> TokenStream {
     body.into()
 }
 
+/// Marks a port in a `.tea.rs` template.
+///
+/// The build transform strips `#[port]` before the template is compiled, so
+/// this only runs when an editor analyses the template as written. It applies
+/// the same rewrite: an incoming port (`-> Sub<Msg>`) returns `Msg`, and an
+/// outgoing port (`-> Cmd<Msg>`) is replaced by the generated helper the
+/// template module already imports.
+#[proc_macro_attribute]
+pub fn port(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut func = parse_macro_input!(item as syn::ItemFn);
+    let returns = match &func.sig.output {
+        syn::ReturnType::Type(_, ty) => match ty.as_ref() {
+            syn::Type::Path(tp) => tp.path.segments.last().map(|seg| seg.ident.to_string()),
+            _ => None,
+        },
+        syn::ReturnType::Default => None,
+    };
+    match returns.as_deref() {
+        Some("Cmd") => TokenStream::new(),
+        Some("Sub") => {
+            func.sig.output = syn::parse_quote!(-> Msg);
+            quote!(#func).into()
+        }
+        // Invalid ports are reported by the build script.
+        _ => quote!(#func).into(),
+    }
+}
+
+/// Generate the runtime in place, without a build script.
+///
+/// Runs the same codegen as `saucer_core::build::generate_runtime`, but takes
+/// the app template by name instead of scanning for the `include!` marker, and
+/// embeds the transformed templates in the expansion:
+///
+/// ```ignore
+/// mod runtime {
+///     saucer_core::runtime! {
+///         app = app,
+///         managers = [saucer_time_manager],
+///     }
+/// }
+///
+/// use runtime::my_app::app;
+/// ```
+///
+/// `app` names a template under `src/` (`app = screens::home` for
+/// `src/screens/home.tea.rs`) and may be repeated. `managers` limits the
+/// runtime to those effect managers; by default every dependency that
+/// declares itself one is used. `reconciler` names the manager whose
+/// reconciler the runtime runs with, saucer-core's by default.
+#[cfg(feature = "runtime")]
+#[proc_macro]
+pub fn runtime(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as runtime::RuntimeArgs);
+    runtime::expand(args).into()
+}
+
 fn is_redacted(field: &syn::Field) -> bool {
     field
         .ident
@@ -166,3 +234,231 @@ fn enum_debug(
         }
     }
 }
+
+/// `RequestFields` for a struct request: the type name and its non-callback fields.
+fn struct_fields(
+    name: &syn::Ident,
+    generics: &syn::Generics,
+    data: &syn::DataStruct,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let fields: Vec<_> = data
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| !is_redacted(f))
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => quote! { (stringify!(#ident), format!("{:?}", self.#ident)) },
+            None => {
+                let idx = syn::Index::from(i);
+                let label = i.to_string();
+                quote! { (#label, format!("{:?}", self.#idx)) }
+            }
+        })
+        .collect();
+    quote! {
+        impl #impl_generics saucer_core::RequestFields for #name #ty_generics #where_clause {
+            fn variant(&self) -> &'static str {
+                stringify!(#name)
+            }
+
+            fn fields(&self) -> Vec<(&'static str, String)> {
+                vec![#(#fields),*]
+            }
+        }
+    }
+}
+
+/// `RequestFields` for an enum request: the variant name and its non-callback fields.
+fn enum_fields(
+    name: &syn::Ident,
+    generics: &syn::Generics,
+    data: &syn::DataEnum,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let variant_arms: Vec<_> = data
+        .variants
+        .iter()
+        .map(|v| {
+            let vident = &v.ident;
+            quote! { #name::#vident { .. } => stringify!(#vident) }
+        })
+        .collect();
+    let field_arms: Vec<_> = data
+        .variants
+        .iter()
+        .map(|v| {
+            let vident = &v.ident;
+            match &v.fields {
+                Fields::Unit => quote! { #name::#vident => Vec::new() },
+                Fields::Unnamed(fields) => {
+                    let bindings: Vec<_> = (0..fields.unnamed.len())
+                        .map(|i| format_ident!("f{}", i))
+                        .collect();
+                    let entries: Vec<_> = fields
+                        .unnamed
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, fld)| !is_redacted(fld))
+                        .map(|(i, _)| {
+                            let b = &bindings[i];
+                            let label = i.to_string();
+                            quote! { (#label, format!("{:?}", #b)) }
+                        })
+                        .collect();
+                    quote! {
+                        #name::#vident( #( #bindings ),* ) => vec![#(#entries),*]
+                    }
+                }
+                Fields::Named(fields) => {
+                    let entries: Vec<_> = fields
+                        .named
+                        .iter()
+                        .filter(|fld| !is_redacted(fld))
+                        .map(|fld| {
+                            let id = fld.ident.as_ref().unwrap();
+                            quote! { (stringify!(#id), format!("{:?}", #id)) }
+                        })
+                        .collect();
+                    let ids = fields
+                        .named
+                        .iter()
+                        .filter(|fld| !is_redacted(fld))
+                        .map(|fld| fld.ident.as_ref().unwrap());
+                    quote! {
+                        #name::#vident { #( #ids, )* .. } => vec![#(#entries),*]
+                    }
+                }
+            }
+        })
+        .collect();
+    quote! {
+        impl #impl_generics saucer_core::RequestFields for #name #ty_generics #where_clause {
+            fn variant(&self) -> &'static str {
+                match self {
+                    #(#variant_arms),*
+                }
+            }
+
+            fn fields(&self) -> Vec<(&'static str, String)> {
+                match self {
+                    #(#field_arms),*
+                }
+            }
+        }
+    }
+}
+
+/// The argument types and output of a `returns: Arc<dyn Fn(..) -> Msg>` field.
+/// Aliased callback types are not recognised and get no `Resolve` impl.
+fn returns_signature(field: &syn::Field) -> Option<(Vec<syn::Type>, syn::Type)> {
+    if field.ident.as_ref()? != "returns" {
+        return None;
+    }
+    let syn::Type::Path(arc) = &field.ty else {
+        return None;
+    };
+    let segment = arc.path.segments.last()?;
+    if segment.ident != "Arc" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let Some(syn::GenericArgument::Type(syn::Type::TraitObject(object))) = args.args.first() else {
+        return None;
+    };
+    object.bounds.iter().find_map(|bound| {
+        let syn::TypeParamBound::Trait(bound) = bound else {
+            return None;
+        };
+        let segment = bound.path.segments.last()?;
+        if segment.ident != "Fn" {
+            return None;
+        }
+        let syn::PathArguments::Parenthesized(args) = &segment.arguments else {
+            return None;
+        };
+        let syn::ReturnType::Type(_, output) = &args.output else {
+            return None;
+        };
+        Some((args.inputs.iter().cloned().collect(), (**output).clone()))
+    })
+}
+
+/// The type a test passes to `Resolve::resolve` for these callback arguments,
+/// and the call that spreads it back out: `()`, the single argument, or a tuple.
+fn resolve_input(inputs: &[syn::Type]) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    match inputs {
+        [] => (quote! { () }, quote! { returns() }),
+        [single] => (quote! { #single }, quote! { returns(result) }),
+        many => {
+            let parts: Vec<_> = (0..many.len()).map(syn::Index::from).collect();
+            (
+                quote! { ( #(#many),* ) },
+                quote! { returns( #(result.#parts),* ) },
+            )
+        }
+    }
+}
+
+fn struct_resolve(
+    name: &syn::Ident,
+    generics: &syn::Generics,
+    data: &syn::DataStruct,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let Some((inputs, output)) = data.fields.iter().find_map(returns_signature) else {
+        return quote! {};
+    };
+    let (input, call) = resolve_input(&inputs);
+    quote! {
+        impl #impl_generics saucer_core::Resolve<#input, #output> for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn resolve(&self, result: #input) -> Option<#output> {
+                let returns = &self.returns;
+                Some(#call)
+            }
+        }
+    }
+}
+
+/// One `Resolve` impl per distinct callback signature, covering every
+/// variant whose `returns` takes it.
+fn enum_resolve(
+    name: &syn::Ident,
+    generics: &syn::Generics,
+    data: &syn::DataEnum,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut groups: Vec<(String, Vec<syn::Type>, syn::Type, Vec<&syn::Ident>)> = Vec::new();
+    for v in &data.variants {
+        let Fields::Named(fields) = &v.fields else {
+            continue;
+        };
+        let Some((inputs, output)) = fields.named.iter().find_map(returns_signature) else {
+            continue;
+        };
+        let key = quote!( (#(#inputs),*) -> #output ).to_string();
+        match groups.iter_mut().find(|(k, ..)| *k == key) {
+            Some((.., variants)) => variants.push(&v.ident),
+            None => groups.push((key, inputs, output, vec![&v.ident])),
+        }
+    }
+    let impls = groups.iter().map(|(_, inputs, output, variants)| {
+        let (input, call) = resolve_input(inputs);
+        quote! {
+            impl #impl_generics saucer_core::Resolve<#input, #output> for #name #ty_generics #where_clause {
+                #[allow(unused_variables)]
+                fn resolve(&self, result: #input) -> Option<#output> {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #( #name::#variants { returns, .. } => Some(#call), )*
+                        _ => None,
+                    }
+                }
+            }
+        }
+    });
+    quote! { #(#impls)* }
+}
@@ -0,0 +1,92 @@
+//! `runtime!`: the build-script codegen, run at macro expansion.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{bracketed, Ident, Token};
+
+use saucer_core_build::RuntimeBuilder;
+
+/// `app = <template>, managers = [<crate>, ...], reconciler = <crate>`
+pub struct RuntimeArgs {
+    apps: Vec<String>,
+    managers: Option<Vec<String>>,
+    reconciler: Option<String>,
+}
+
+impl Parse for RuntimeArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = RuntimeArgs {
+            apps: Vec::new(),
+            managers: None,
+            reconciler: None,
+        };
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_str() {
+                "app" => {
+                    let path: syn::Path = input.parse()?;
+                    let segments: Vec<String> =
+                        path.segments.iter().map(|s| s.ident.to_string()).collect();
+                    args.apps.push(segments.join("::"));
+                }
+                "managers" => {
+                    let content;
+                    bracketed!(content in input);
+                    let crates = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+                    args.managers = Some(crates.iter().map(Ident::to_string).collect());
+                }
+                "reconciler" => args.reconciler = Some(input.parse::<Ident>()?.to_string()),
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "expected `app`, `managers` or `reconciler`",
+                    ))
+                }
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        if args.apps.is_empty() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "missing `app = <template>`",
+            ));
+        }
+        Ok(args)
+    }
+}
+
+pub fn expand(args: RuntimeArgs) -> TokenStream {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .expect("CARGO_MANIFEST_DIR not set - runtime! must be expanded by cargo");
+    let mut builder = RuntimeBuilder::new().manifest_dir(manifest_dir);
+    for app in args.apps {
+        builder = builder.app(app);
+    }
+    if let Some(managers) = args.managers {
+        builder = builder.only_managers(managers);
+    }
+    if let Some(reconciler) = args.reconciler {
+        builder = builder.reconciler(reconciler);
+    }
+
+    match builder
+        .resolve()
+        .and_then(|plan| plan.inline_runtime_source())
+    {
+        Ok(source) => source.parse().expect("generated runtime should tokenize"),
+        Err(errors) => errors
+            .iter()
+            .map(|error| {
+                // rustc adds its own `error: `.
+                let rendered = error.render();
+                let message = rendered.strip_prefix("error: ").unwrap_or(&rendered);
+                quote! { compile_error!(#message); }
+            })
+            .collect(),
+    }
+}
@@ -1,3 +1,5 @@
+use crate::{AsRequest, CmdSnapshot, Describe};
+
 /// Generic command container - holds a batch of requests.
 /// The request type `Req` is provided by the app's generated code.
 pub struct CoreCmd<Req>(pub Vec<Req>);
@@ -26,6 +28,39 @@ impl<Req> CoreCmd<Req> {
     pub fn into_inner(self) -> Vec<Req> {
         self.0
     }
+
+    /// Every request of type `T`, e.g. `cmd.requests::<HttpRequest<_>>()`.
+    pub fn requests<T>(&self) -> Vec<&T>
+    where
+        Req: AsRequest<T>,
+    {
+        self.0.iter().filter_map(AsRequest::as_request).collect()
+    }
+
+    /// The first request of type `T` matching `pred`.
+    pub fn find_request<T>(&self, pred: impl Fn(&T) -> bool) -> Option<&T>
+    where
+        Req: AsRequest<T>,
+    {
+        self.requests::<T>().into_iter().find(|req| pred(req))
+    }
+
+    /// Whether any request of type `T` matches `pred`.
+    pub fn contains_request<T>(&self, pred: impl Fn(&T) -> bool) -> bool
+    where
+        Req: AsRequest<T>,
+    {
+        self.find_request(pred).is_some()
+    }
+}
+
+impl<Req: Describe> CoreCmd<Req> {
+    /// The command as comparable data, callbacks left out.
+    pub fn describe(&self) -> CmdSnapshot {
+        CmdSnapshot {
+            requests: self.0.iter().map(Describe::describe).collect(),
+        }
+    }
 }
 
 impl<Req> Default for CoreCmd<Req> {
@@ -0,0 +1,108 @@
+//! Inspecting commands without running them, for unit-testing `update`.
+
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// A request's name and non-callback fields.
+///
+/// `#[derive(Request)]` implements this alongside its redacted `Debug`;
+/// hand-written request types list every field except `returns`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not describe its fields for command snapshots",
+    note = "derive `saucer_core::Request`, or implement `RequestFields` by hand"
+)]
+pub trait RequestFields {
+    /// The enum variant, or the type name for struct requests.
+    fn variant(&self) -> &'static str;
+    /// Field names with their `Debug` rendering; callbacks are left out.
+    fn fields(&self) -> Vec<(&'static str, String)>;
+}
+
+/// Implemented by the generated `Request` enum to tag each request with the
+/// manager that handles it, as long as every manager's request type
+/// implements [`RequestFields`].
+pub trait Describe {
+    fn describe(&self) -> RequestSnapshot;
+}
+
+/// Borrow a specific manager's request out of the generated `Request` enum.
+pub trait AsRequest<T> {
+    fn as_request(&self) -> Option<&T>;
+}
+
+/// Call a request's `returns` callback with a fake result.
+///
+/// Returns `None` when the request's callback does not take a `T` (another
+/// variant of the same request enum).
+pub trait Resolve<T, Msg> {
+    fn resolve(&self, result: T) -> Option<Msg>;
+}
+
+/// One request as data: comparable, printable, free of callbacks.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RequestSnapshot {
+    pub manager: &'static str,
+    pub variant: &'static str,
+    #[serde(serialize_with = "fields_as_map")]
+    pub fields: Vec<(&'static str, String)>,
+}
+
+fn fields_as_map<S: Serializer>(
+    fields: &[(&'static str, String)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(fields.len()))?;
+    for (name, value) in fields {
+        map.serialize_entry(name, value)?;
+    }
+    map.end()
+}
+
+impl RequestSnapshot {
+    pub fn of(manager: &'static str, request: &impl RequestFields) -> Self {
+        RequestSnapshot {
+            manager,
+            variant: request.variant(),
+            fields: request.fields(),
+        }
+    }
+
+    /// The `Debug` rendering of the field called `name`.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl fmt::Display for RequestSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.manager, self.variant)?;
+        if !self.fields.is_empty() {
+            let fields: Vec<String> = self
+                .fields
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, value))
+                .collect();
+            write!(f, " {{ {} }}", fields.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Every request in a command, in the order the runtime would dispatch them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CmdSnapshot {
+    pub requests: Vec<RequestSnapshot>,
+}
+
+impl fmt::Display for CmdSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for request in &self.requests {
+            writeln!(f, "{}", request)?;
+        }
+        Ok(())
+    }
+}
@@ -1,20 +1,34 @@
 mod cmd;
+mod inspect;
+mod manager;
 mod observation;
 mod observer;
 mod ports_plumbing;
 mod reconciler;
+mod replay;
 mod request;
 mod router;
 mod sender;
+mod sub;
 
 pub use cmd::CoreCmd;
+pub use inspect::{AsRequest, CmdSnapshot, Describe, RequestFields, RequestSnapshot, Resolve};
+pub use manager::{EffectManager, ManagerInput, SubscriptionManager};
 pub use observation::Observation;
-pub use observer::{filter_observer, no_op_observer, tee_observer, tracing_observer, ObserverFn};
+pub use observer::{
+    filter_observer, jsonl_observer, no_op_observer, tee_observer, tracing_observer, ObserverFn,
+};
 pub use ports_plumbing::Sub;
 pub use reconciler::{no_op_reconciler, CoreManager};
+pub use replay::{replay, ReplayError};
 pub use request::{shutdown, CoreRequest};
 pub use router::{Router, RouterChannels};
-pub use sender::{EffectManager, SendToManager};
+#[cfg(feature = "runtime-macro")]
+pub use saucer_core_macros::runtime;
+pub use saucer_core_macros::{port, Request};
+pub use sender::SendToManager;
+pub use sub::{no_subscriptions, CoreSub};
 
+/// Build-script code generation; see [`saucer_core_build`].
 #[cfg(feature = "build")]
-pub mod build;
+pub use saucer_core_build as build;
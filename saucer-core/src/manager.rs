@@ -0,0 +1,68 @@
+use crate::router::Router;
+
+/// Interface the generated runtime uses to drive an effect manager.
+///
+/// Managers are stateless handlers: the runtime owns each manager's `State`
+/// and threads it through every call. Generated code only calls managers
+/// through this trait, so a mismatched signature is reported at the manager's
+/// own `impl` instead of inside `runtime.rs`.
+pub trait EffectManager {
+    /// State the runtime keeps for this manager between calls.
+    type State<Msg>;
+    /// Requests apps send to this manager (the metadata `request_type`).
+    type Request<Msg>;
+    /// Messages the manager sends itself via `Router::send_to_self`
+    /// (the metadata `self_msg_type`).
+    type SelfMsg;
+
+    /// Fresh state, created once when the runtime starts.
+    fn init<Msg: Send + 'static>() -> Self::State<Msg>;
+
+    /// Handles requests issued by the app's `init` and `update`.
+    fn on_effects<Msg: Send + 'static>(
+        &self,
+        router: &Router<Msg, Self::SelfMsg>,
+        state: Self::State<Msg>,
+        effects: Vec<Self::Request<Msg>>,
+    ) -> Self::State<Msg>;
+
+    /// Handles a message the manager sent itself. Managers that never call
+    /// `Router::send_to_self` can keep the default.
+    fn on_self_msg<Msg: Send + 'static>(
+        &self,
+        router: &Router<Msg, Self::SelfMsg>,
+        state: Self::State<Msg>,
+        msg: Self::SelfMsg,
+    ) -> Self::State<Msg> {
+        let _ = (router, msg);
+        state
+    }
+}
+
+/// Effect managers that also accept subscriptions (the metadata
+/// `subscription_type`).
+pub trait SubscriptionManager: EffectManager {
+    /// Subscriptions apps return from `subscriptions`.
+    type Subscription<Msg>;
+
+    /// Receives the app's complete current set of subscriptions after init
+    /// and after every update.
+    fn on_subscriptions<Msg: Send + 'static>(
+        &self,
+        router: &Router<Msg, Self::SelfMsg>,
+        state: Self::State<Msg>,
+        subs: Vec<Self::Subscription<Msg>>,
+    ) -> Self::State<Msg>;
+}
+
+/// Work the runtime forwards to a manager that runs on its own task
+/// (`spawn = true` in its metadata). The task owns the manager's state and
+/// handles inputs in the order they were sent.
+pub enum ManagerInput<Req, SelfMsg, Sub> {
+    /// One update's requests for this manager.
+    Effects(Vec<Req>),
+    /// A message the manager sent itself.
+    SelfMsg(SelfMsg),
+    /// The app's complete current subscription set.
+    Subscriptions(Vec<Sub>),
+}
@@ -1,6 +1,12 @@
-use crate::Observation;
+use crate::{Describe, Observation};
+use serde::Serialize;
+use serde_json::{json, Value};
 use std::fmt::Debug;
-use std::sync::Arc;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Observer function type
 pub type ObserverFn<EventType, CommandType, SelfMsgType> =
@@ -40,6 +46,82 @@ where
     )
 }
 
+/// JSONL observer - appends one JSON object per observation to `log_dir/events.jsonl`
+///
+/// Each call starts a session with a `start` record, so [`crate::replay`]
+/// can pick the latest run out of an appended log. Effects are recorded as
+/// their [`crate::RequestSnapshot`] and manager messages as `Debug` text;
+/// only `EventType` has to be serializable.
+///
+/// Panics if the log file can't be created; later write failures are logged
+/// through `tracing` and dropped.
+pub fn jsonl_observer<EventType, CommandType, SelfMsgType, P>(
+    log_dir: P,
+) -> ObserverFn<EventType, CommandType, SelfMsgType>
+where
+    EventType: Serialize + 'static,
+    CommandType: Describe + 'static,
+    SelfMsgType: Debug + 'static,
+    P: AsRef<Path>,
+{
+    let log_dir = log_dir.as_ref();
+    std::fs::create_dir_all(log_dir)
+        .unwrap_or_else(|e| panic!("jsonl_observer: create {}: {}", log_dir.display(), e));
+    let path = log_dir.join("events.jsonl");
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .unwrap_or_else(|e| panic!("jsonl_observer: open {}: {}", path.display(), e));
+    let writer = Mutex::new(BufWriter::with_capacity(64 * 1024, file));
+    write_record(
+        &writer,
+        &json!({ "type": "start", "ts": seconds(SystemTime::now()) }),
+    );
+
+    Arc::new(
+        move |observation: &Observation<EventType, CommandType, SelfMsgType>| {
+            let record = match observation {
+                Observation::Event { ts, data } => match serde_json::to_value(data) {
+                    Ok(data) => json!({ "type": "event", "ts": seconds(*ts), "data": data }),
+                    Err(e) => {
+                        tracing::warn!(target: "saucer-core::jsonl", "unserializable Msg: {}", e);
+                        return;
+                    }
+                },
+                Observation::Effect { ts, data } => {
+                    json!({ "type": "effect", "ts": seconds(*ts), "data": data.describe() })
+                }
+                Observation::ManagerMsg { ts, manager, data } => json!({
+                    "type": "manager_msg",
+                    "ts": seconds(*ts),
+                    "manager": manager,
+                    "data": format!("{:?}", data),
+                }),
+            };
+            write_record(&writer, &record);
+        },
+    )
+}
+
+fn seconds(ts: SystemTime) -> f64 {
+    ts.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+fn write_record(writer: &Mutex<BufWriter<File>>, record: &Value) {
+    let mut writer = writer.lock().unwrap_or_else(PoisonError::into_inner);
+    // Flush per line so a crashed session still leaves a replayable log.
+    let written = serde_json::to_writer(&mut *writer, record)
+        .map_err(std::io::Error::from)
+        .and_then(|()| writer.write_all(b"\n"))
+        .and_then(|()| writer.flush());
+    if let Err(e) = written {
+        tracing::warn!(target: "saucer-core::jsonl", "failed to write observation: {}", e);
+    }
+}
+
 /// Filter observer - include/exclude types
 pub fn filter_observer<EventType, CommandType, SelfMsgType>(
     wrapped: ObserverFn<EventType, CommandType, SelfMsgType>,
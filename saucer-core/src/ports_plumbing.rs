@@ -1,5 +1,6 @@
-/// Marker type used in templates to declare incoming ports (host → app).
-/// Purely a build-script signal; not used at runtime.
+/// Marker type used in templates for incoming ports (host → app) and the
+/// `subscriptions` function. Purely a build-script signal; template imports of
+/// `saucer_core::Sub` are rewritten to the generated `Sub<Msg>` alias.
 pub struct Sub<Msg>(pub std::marker::PhantomData<Msg>);
 
 impl<Msg> Sub<Msg> {
@@ -1,11 +1,29 @@
-use crate::sender::{EffectManager, SendToManager};
+use crate::manager::EffectManager;
+use crate::request::CoreRequest;
+use crate::router::Router;
+use crate::sender::SendToManager;
 
 /// Core manager - used for apps without view reconciliation.
 /// Has SelfMsg = () since it doesn't process self-messages.
 pub struct CoreManager;
 
 impl EffectManager for CoreManager {
+    type State<Msg> = ();
+    type Request<Msg> = CoreRequest;
     type SelfMsg = ();
+
+    fn init<Msg: Send + 'static>() {}
+
+    /// Core requests are interpreted by the runtime loop itself (e.g. shutdown),
+    /// so there is nothing left to do here.
+    fn on_effects<Msg: Send + 'static>(
+        &self,
+        _router: &Router<Msg, ()>,
+        state: (),
+        _effects: Vec<CoreRequest>,
+    ) {
+        state
+    }
 }
 
 //TODO: no_op_reconcile doesn't do anything so sholud not use SendToManager
@@ -0,0 +1,150 @@
+//! Replaying a [`crate::jsonl_observer`] recording through `update`.
+//!
+//! The recorded messages are fed back through `init` and `update` without
+//! managers, so a bug's final `Model` can be rebuilt deterministically. The
+//! effects each step emits are checked against the recorded ones; the first
+//! mismatch stops the replay.
+
+use crate::{CoreCmd, Describe};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Why a recording could not be replayed.
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The log could not be read.
+    Io { path: PathBuf, message: String },
+    /// A line is not valid JSON, or an event does not deserialize into `Msg`.
+    Parse { line: usize, message: String },
+    /// The replayed effects differ from the recorded ones.
+    Diverged {
+        /// Line of the recorded effect that did not match.
+        line: usize,
+        /// Line of the event whose `update` emitted the mismatch; `None` for `init`.
+        cause: Option<usize>,
+        recorded: String,
+        /// `None` when replay ran out of effects before the recording did.
+        emitted: Option<String>,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io { path, message } => write!(f, "read {}: {}", path.display(), message),
+            ReplayError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            ReplayError::Diverged {
+                line,
+                cause,
+                recorded,
+                emitted,
+            } => {
+                write!(f, "replay diverged at line {}: recorded {}", line, recorded)?;
+                match (emitted, cause) {
+                    (Some(emitted), Some(cause)) => write!(
+                        f,
+                        ", but update for the message on line {} emitted {}",
+                        cause, emitted
+                    ),
+                    (Some(emitted), None) => write!(f, ", but init emitted {}", emitted),
+                    (None, _) => write!(f, ", but replay emitted nothing more"),
+                }
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Replay the latest session in `path` (a `jsonl_observer` `events.jsonl`)
+/// and return the resulting model.
+///
+/// The recording may stop short of what replay emits (a shutdown drops
+/// later requests, a crash drops whatever was still queued), but every
+/// recorded effect must match the replayed one at the same position.
+pub fn replay<Model, Msg, Req>(
+    path: impl AsRef<Path>,
+    init: impl FnOnce() -> (Model, CoreCmd<Req>),
+    update: impl Fn(Model, Msg) -> (Model, CoreCmd<Req>),
+) -> Result<Model, ReplayError>
+where
+    Msg: DeserializeOwned,
+    Req: Describe,
+{
+    let path = path.as_ref();
+    let source = std::fs::read_to_string(path).map_err(|e| ReplayError::Io {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    let mut records = Vec::new();
+    for (index, text) in source.lines().enumerate() {
+        if text.trim().is_empty() {
+            continue;
+        }
+        let record: Value = serde_json::from_str(text).map_err(|e| ReplayError::Parse {
+            line: index + 1,
+            message: e.to_string(),
+        })?;
+        records.push((index + 1, record));
+    }
+    let session_start = records
+        .iter()
+        .rposition(|(_, record)| record["type"] == "start")
+        .map_or(0, |i| i + 1);
+
+    let mut events = Vec::new();
+    let mut recorded = Vec::new();
+    for (line, record) in records.drain(session_start..) {
+        match record["type"].as_str() {
+            Some("event") => {
+                let msg = serde_json::from_value(record["data"].clone()).map_err(|e| {
+                    ReplayError::Parse {
+                        line,
+                        message: e.to_string(),
+                    }
+                })?;
+                events.push((line, msg));
+            }
+            Some("effect") => recorded.push((line, record["data"].clone())),
+            _ => {}
+        }
+    }
+
+    let mut emitted = Vec::new();
+    let (mut model, cmd) = init();
+    describe_into(&mut emitted, None, &cmd);
+    for (line, msg) in events {
+        let (next, cmd) = update(model, msg);
+        model = next;
+        describe_into(&mut emitted, Some(line), &cmd);
+    }
+
+    for (i, (line, expected)) in recorded.into_iter().enumerate() {
+        let diverged = |cause, emitted| ReplayError::Diverged {
+            line,
+            cause,
+            recorded: expected.to_string(),
+            emitted,
+        };
+        match emitted.get(i) {
+            Some((_, actual)) if *actual == expected => {}
+            Some((cause, actual)) => return Err(diverged(*cause, Some(actual.to_string()))),
+            None => return Err(diverged(None, None)),
+        }
+    }
+    Ok(model)
+}
+
+fn describe_into<Req: Describe>(
+    emitted: &mut Vec<(Option<usize>, Value)>,
+    cause: Option<usize>,
+    cmd: &CoreCmd<Req>,
+) {
+    for request in cmd.describe().requests {
+        let value = serde_json::to_value(request).expect("snapshots serialize");
+        emitted.push((cause, value));
+    }
+}
@@ -7,3 +7,15 @@ pub enum CoreRequest {
 pub fn shutdown() -> CoreRequest {
     CoreRequest::Shutdown
 }
+
+impl crate::RequestFields for CoreRequest {
+    fn variant(&self) -> &'static str {
+        match self {
+            CoreRequest::Shutdown => "Shutdown",
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+}
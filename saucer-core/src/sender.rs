@@ -1,12 +1,8 @@
+use crate::manager::EffectManager;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use tokio::sync::mpsc::UnboundedSender;
 
-/// Trait for effect managers - defines associated SelfMsg type.
-pub trait EffectManager {
-    type SelfMsg;
-}
-
 /// Restricted sender that only allows sending messages to a specific manager.
 /// This prevents reconcilers from accidentally sending app events directly.
 pub struct SendToManager<Manager: EffectManager, WireMsg> {
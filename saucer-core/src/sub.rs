@@ -0,0 +1,41 @@
+/// Generic subscription container - holds the set of subscriptions an app
+/// currently wants. The subscription type `S` is provided by the app's
+/// generated code.
+pub struct CoreSub<S>(pub Vec<S>);
+
+impl<S> CoreSub<S> {
+    /// No subscriptions
+    pub fn none() -> Self {
+        CoreSub(Vec::new())
+    }
+
+    /// Single subscription
+    pub fn single(sub: S) -> Self {
+        CoreSub(vec![sub])
+    }
+
+    /// Batch multiple subscriptions together
+    pub fn batch(subs: impl IntoIterator<Item = CoreSub<S>>) -> Self {
+        let mut all = Vec::new();
+        for CoreSub(mut v) in subs {
+            all.append(&mut v);
+        }
+        CoreSub(all)
+    }
+
+    /// Access inner subscriptions (for runtime dispatch)
+    pub fn into_inner(self) -> Vec<S> {
+        self.0
+    }
+}
+
+impl<S> Default for CoreSub<S> {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+/// Subscriptions function for apps that don't subscribe to anything.
+pub fn no_subscriptions<Model, S>() -> impl Fn(&Model) -> CoreSub<S> {
+    |_model: &Model| CoreSub::none()
+}
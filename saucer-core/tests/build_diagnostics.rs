@@ -0,0 +1,40 @@
+// mock-broken-app's build script keeps what `try_generate_runtime` reports
+// instead of failing, so the diagnostics can be checked here.
+#[test]
+fn codegen_reports_every_problem_in_one_pass() {
+    let diagnostics = mock_broken_app::DIAGNOSTICS;
+    let errors: Vec<&str> = diagnostics.split("\n\n").collect();
+    assert_eq!(errors.len(), 6, "unexpected diagnostics:\n{}", diagnostics);
+
+    // Templates left out of the crate's package.
+    assert!(errors[0].starts_with("error: crate excludes its templates from its package"));
+    assert!(errors[0].contains("mock_broken_app/Cargo.toml"));
+
+    // Unknown template imported by the runtime host.
+    assert!(errors[1].starts_with("error: template missing.tea.rs not found locally"));
+    assert!(errors[1].contains("mock_broken_app/src/lib.rs:15:41"));
+    assert!(errors[1].contains(
+        "15 | pub use runtime::mock_broken_app::{app, missing};\n   |                                         ^^^^^^^"
+    ));
+
+    // Glob import of a template crate.
+    assert!(errors[2].starts_with("error: glob imports from `mock-widget`"));
+    assert!(errors[2].contains("mock_broken_app/src/app.tea.rs:3:18"));
+    assert!(errors[2].contains("= help: import templates explicitly"));
+
+    // Port with a return type that is neither Cmd nor Sub.
+    assert!(errors[3].starts_with("error: port function `send` must return Sub<Msg>"));
+    assert!(errors[3].contains("mock_broken_app/src/app.tea.rs:23:28"));
+    assert!(errors[3]
+        .contains("23 | pub fn send(value: u32) -> u32 {\n   |                            ^^^"));
+
+    // Core plumbing imported inside a group; the span is the offending name.
+    assert!(errors[4].starts_with("error: template imports saucer_core::CoreCmd"));
+    assert!(errors[4].contains("mock_broken_app/src/app.tea.rs:1:24"));
+    assert!(errors[4]
+        .contains("1 | use saucer_core::{Cmd, CoreCmd};\n  |                        ^^^^^^^"));
+
+    // Helper imported from a manager that does not declare it.
+    assert!(errors[5].starts_with("error: helper `notify_soon` is imported"));
+    assert!(errors[5].contains("mock_time_manager/src/requests.rs"));
+}
@@ -0,0 +1,79 @@
+use saucer_core::{CoreManager, EffectManager, Router, RouterChannels};
+
+/// Counts requests, acknowledging each batch through a self-message.
+struct Counter;
+
+#[derive(Debug, PartialEq)]
+enum CounterMsg {
+    Flush,
+}
+
+impl EffectManager for Counter {
+    type State<Msg> = u32;
+    type Request<Msg> = Msg;
+    type SelfMsg = CounterMsg;
+
+    fn init<Msg: Send + 'static>() -> u32 {
+        0
+    }
+
+    fn on_effects<Msg: Send + 'static>(
+        &self,
+        router: &Router<Msg, CounterMsg>,
+        state: u32,
+        effects: Vec<Msg>,
+    ) -> u32 {
+        let count = effects.len() as u32;
+        for msg in effects {
+            router.send_to_app(msg);
+        }
+        router.send_to_self(CounterMsg::Flush);
+        state + count
+    }
+
+    fn on_self_msg<Msg: Send + 'static>(
+        &self,
+        _router: &Router<Msg, CounterMsg>,
+        state: u32,
+        msg: CounterMsg,
+    ) -> u32 {
+        assert_eq!(msg, CounterMsg::Flush);
+        state * 10
+    }
+}
+
+/// Drives any manager the way the generated runtime does: init, one batch of
+/// effects, then every self-message the batch produced.
+fn drive<M, Msg>(manager: M, effects: Vec<M::Request<Msg>>) -> (M::State<Msg>, Vec<Msg>)
+where
+    M: EffectManager,
+    Msg: Send + 'static,
+{
+    let RouterChannels {
+        router,
+        mut app_rx,
+        mut self_rx,
+    } = RouterChannels::<Msg, M::SelfMsg>::new();
+    let mut state = manager.on_effects(&router, M::init(), effects);
+    while let Ok(msg) = self_rx.try_recv() {
+        state = manager.on_self_msg(&router, state, msg);
+    }
+    let mut delivered = Vec::new();
+    while let Ok(msg) = app_rx.try_recv() {
+        delivered.push(msg);
+    }
+    (state, delivered)
+}
+
+#[test]
+fn managers_can_be_driven_generically() {
+    let (state, delivered) = drive(Counter, vec!["a", "b", "c"]);
+    assert_eq!(state, 30);
+    assert_eq!(delivered, vec!["a", "b", "c"]);
+}
+
+#[test]
+fn core_manager_accepts_core_requests() {
+    let ((), delivered) = drive::<_, ()>(CoreManager, vec![saucer_core::shutdown()]);
+    assert!(delivered.is_empty());
+}
@@ -17,7 +17,7 @@ pub struct Model {
     pub req_time: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub enum Msg {
     GotTime(String),
     GotApiResponse(String),
@@ -45,7 +45,7 @@ pub fn update(model: Model, msg: Msg) -> (Model, Cmd<Msg>) {
             (model, get(url, |resp: Result<mock_http_manager::HttpResponse, mock_http_manager::HttpError>| {
                 match resp {
                     Ok(r) => Msg::GotApiResponse(String::from_utf8_lossy(&r.body).to_string()),
-                    Err(e) => Msg::GotApiResponse(format!("error: {}", e.message)),
+                    Err(e) => Msg::GotApiResponse(format!("error: {}", e)),
                 }
             }))
         }
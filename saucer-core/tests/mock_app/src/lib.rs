@@ -4,10 +4,16 @@ pub mod runtime {
 
 pub use runtime::mock_app::app;
 use runtime::sync::Runtime;
+use saucer_core::ObserverFn;
 use std::time::Duration;
 use tokio::runtime::Builder;
 
 pub fn run_app() {
+    run_app_with(saucer_core::tracing_observer())
+}
+
+/// `run_app`, with `observer` seeing every observation.
+pub fn run_app_with(observer: ObserverFn<app::Msg, runtime::Request<app::Msg>, runtime::SelfMsg>) {
     // Minimal logger to keep output quiet during tests
     let _ = tracing_subscriber::fmt()
         .with_max_level(tracing::Level::INFO)
@@ -21,9 +27,10 @@ pub fn run_app() {
     let runtime = Runtime::new(
         app::init,
         app::update,
+        saucer_core::no_subscriptions(),
         app::view,
         saucer_core::no_op_reconciler(),
-        saucer_core::tracing_observer(),
+        observer,
     );
 
     rt.block_on(async {
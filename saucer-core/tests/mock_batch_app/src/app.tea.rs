@@ -0,0 +1,57 @@
+//! App module - template transformed by saucer_core build script.
+//!
+//! One update issues two GETs, so the HTTP manager should receive them as a
+//! single batch. The app shuts down once both responses are in.
+
+use saucer_core::Cmd;
+use saucer_core::command::shutdown;
+use mock_time_manager::command::notify_after;
+use mock_http_manager::command::get;
+
+pub struct Model {
+    pub req_time: Option<String>,
+    pub responses: usize,
+    pub last_response: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Msg {
+    GotTime(String),
+    GotApiResponse(String),
+}
+
+pub fn init() -> (Model, Cmd<Msg>) {
+    let model = Model { req_time: None, responses: 0, last_response: None };
+    let cmd = notify_after(0, ::std::time::Duration::from_millis(0), || "noon".to_string())
+        .map(Msg::GotTime);
+    (model, cmd)
+}
+
+pub fn update(model: Model, msg: Msg) -> (Model, Cmd<Msg>) {
+    match msg {
+        Msg::GotTime(formatted) => {
+            let model = Model { req_time: Some(formatted.clone()), ..model };
+            let fetch = |path: &str| {
+                let url = format!("https://api.example.com/{}?ts={}", path, formatted);
+                get(url, |resp: Result<mock_http_manager::HttpResponse, mock_http_manager::HttpError>| {
+                    match resp {
+                        Ok(r) => Msg::GotApiResponse(String::from_utf8_lossy(&r.body).to_string()),
+                        Err(e) => Msg::GotApiResponse(format!("error: {}", e)),
+                    }
+                })
+            };
+            // Both requests come from one update, so the manager should get them together.
+            (model, Cmd::batch([fetch("data"), fetch("meta")]))
+        }
+        Msg::GotApiResponse(response) => {
+            let model = Model { responses: model.responses + 1, last_response: Some(response), ..model };
+            if model.responses == 2 {
+                (model, shutdown())
+            } else {
+                (model, Cmd::none())
+            }
+        }
+    }
+}
+
+pub fn view(_model: &Model) -> () { () }
@@ -0,0 +1,49 @@
+pub mod runtime {
+    include!(concat!(env!("OUT_DIR"), "/runtime.rs"));
+}
+
+pub use runtime::mock_batch_app::app;
+use runtime::sync::Runtime;
+use saucer_core::{Observation, ObserverFn};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::Builder;
+
+/// Run the app to shutdown and return the API response bodies it received.
+pub fn run_app() -> Vec<String> {
+    let rt = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("tokio runtime");
+
+    let responses = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&responses);
+    let recorder: ObserverFn<app::Msg, runtime::Request<app::Msg>, runtime::SelfMsg> =
+        Arc::new(move |observation| {
+            if let Observation::Event {
+                data: app::Msg::GotApiResponse(body),
+                ..
+            } = observation
+            {
+                seen.lock().unwrap().push(body.clone());
+            }
+        });
+
+    let runtime = Runtime::new(
+        app::init,
+        app::update,
+        saucer_core::no_subscriptions(),
+        app::view,
+        saucer_core::no_op_reconciler(),
+        recorder,
+    );
+
+    rt.block_on(async {
+        tokio::time::timeout(Duration::from_secs(1), runtime.run())
+            .await
+            .expect("runtime should complete before timeout")
+    });
+
+    let responses = responses.lock().unwrap().clone();
+    responses
+}
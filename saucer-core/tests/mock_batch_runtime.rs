@@ -0,0 +1,14 @@
+// One update issues two GETs; the manager should receive them in a single call.
+#[test]
+fn mock_batch_runtime_batches_requests_per_update() {
+    let responses = mock_batch_app::run_app();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(
+        responses[0],
+        "GET https://api.example.com/data?ts=noon (batch of 2)"
+    );
+    assert_eq!(
+        responses[1],
+        "GET https://api.example.com/meta?ts=noon (batch of 2)"
+    );
+}
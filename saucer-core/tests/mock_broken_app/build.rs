@@ -0,0 +1,15 @@
+use std::path::Path;
+
+fn main() {
+    // The templates are broken on purpose; keep the diagnostics for the tests
+    // instead of failing the build.
+    let errors = saucer_core::build::try_generate_runtime()
+        .expect_err("mock-broken-app should not generate a runtime");
+    let rendered: Vec<String> = errors.iter().map(|e| e.render()).collect();
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR");
+    std::fs::write(
+        Path::new(&out_dir).join("diagnostics.txt"),
+        rendered.join("\n\n"),
+    )
+    .expect("write diagnostics");
+}
@@ -0,0 +1,25 @@
+use saucer_core::{Cmd, CoreCmd};
+use mock_time_manager::command::notify_soon;
+use mock_widget::*;
+
+pub struct Model {}
+
+#[derive(Clone, Debug)]
+pub enum Msg {
+    Ping,
+}
+
+pub fn init() -> (Model, Cmd<Msg>) {
+    (Model {}, notify_soon(|| Msg::Ping))
+}
+
+pub fn update(model: Model, _msg: Msg) -> (Model, Cmd<Msg>) {
+    (model, Cmd::none())
+}
+
+pub fn view(_m: &Model) {}
+
+#[port]
+pub fn send(value: u32) -> u32 {
+    value
+}
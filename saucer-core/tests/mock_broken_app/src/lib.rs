@@ -0,0 +1,27 @@
+//! An app whose templates are wrong in several ways at once. Its build script
+//! records what codegen reports instead of failing.
+
+/// Every problem found by `try_generate_runtime`, rendered as diagnostics.
+pub const DIAGNOSTICS: &str = include_str!(concat!(env!("OUT_DIR"), "/diagnostics.txt"));
+
+// Compiled out: codegen only needs to see the include, the template imports
+// and the runtime call.
+#[cfg(any())]
+pub mod runtime {
+    include!(concat!(env!("OUT_DIR"), "/runtime.rs"));
+}
+
+#[cfg(any())]
+pub use runtime::mock_broken_app::{app, missing};
+
+#[cfg(any())]
+pub fn run_app() {
+    let _runtime = runtime::sync::Runtime::new(
+        app::init,
+        app::update,
+        saucer_core::no_subscriptions(),
+        app::view,
+        saucer_core::no_op_reconciler(),
+        saucer_core::no_op_observer(),
+    );
+}
@@ -0,0 +1,71 @@
+use mock_batch_app::app::{self, Model, Msg};
+use mock_http_manager::{HttpRequest, HttpResponse};
+use mock_time_manager::TimeRequest;
+use saucer_core::{CoreRequest, Resolve};
+
+fn model() -> Model {
+    Model {
+        req_time: None,
+        responses: 0,
+        last_response: None,
+    }
+}
+
+#[test]
+fn describe_lists_requests_without_callbacks() {
+    let (_, cmd) = app::init();
+    let snapshot = cmd.describe();
+    assert_eq!(snapshot.requests.len(), 1);
+    let timer = &snapshot.requests[0];
+    assert_eq!(timer.manager, "mock_time_manager");
+    assert_eq!(timer.variant, "NotifyAfter");
+    assert_eq!(timer.field("duration"), Some("0ns"));
+    assert_eq!(
+        snapshot.to_string(),
+        "mock_time_manager::NotifyAfter { id: 0, duration: 0ns }\n"
+    );
+
+    let fired = cmd.requests::<TimeRequest<_>>()[0].resolve(());
+    assert!(matches!(fired, Some(Msg::GotTime(_))));
+}
+
+// `update` returns two GETs; find them by type and field, then feed one a
+// fake response to get the message the app would receive.
+#[test]
+fn requests_can_be_matched_by_type_and_resolved() {
+    let (_, cmd) = app::update(model(), Msg::GotTime("noon".into()));
+    let snapshot = cmd.describe();
+    assert!(snapshot
+        .requests
+        .iter()
+        .all(|r| r.manager == "mock_http_manager" && r.variant == "HttpRequest"));
+    assert_eq!(
+        snapshot.requests[1].field("url"),
+        Some(r#""https://api.example.com/meta?ts=noon""#)
+    );
+
+    assert_eq!(cmd.requests::<HttpRequest<_>>().len(), 2);
+    assert!(cmd.contains_request::<HttpRequest<_>>(|r| r.url.contains("/data?")));
+    assert!(!cmd.contains_request::<CoreRequest>(|_| true));
+
+    let get = cmd
+        .find_request::<HttpRequest<_>>(|r| r.url.contains("/meta?"))
+        .expect("meta request");
+    let msg = get.resolve(Ok(HttpResponse {
+        status: 200,
+        headers: Vec::new(),
+        body: b"hello".to_vec(),
+    }));
+    assert!(matches!(msg, Some(Msg::GotApiResponse(body)) if body == "hello"));
+}
+
+#[test]
+fn shutdown_is_a_core_request() {
+    let model = Model {
+        responses: 1,
+        ..model()
+    };
+    let (_, cmd) = app::update(model, Msg::GotApiResponse(String::new()));
+    assert!(cmd.contains_request::<CoreRequest>(|r| matches!(r, CoreRequest::Shutdown)));
+    assert_eq!(cmd.describe().to_string(), "saucer_core::Shutdown\n");
+}
@@ -0,0 +1,75 @@
+use mock_port_app::app;
+use mock_port_app::runtime::{self, sync::Runtime};
+use saucer_core::{CoreManager, SendToManager};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::Builder;
+
+async fn until(mut ready: impl FnMut() -> bool) {
+    while !ready() {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+}
+
+// Jumping re-renders a past model and holds new messages back; resuming
+// renders the live model again and lets them through.
+#[test]
+fn debugger_jumps_to_past_models_and_resumes() {
+    let rt = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("tokio runtime");
+
+    let views = Arc::new(Mutex::new(Vec::new()));
+    let rendered = Arc::clone(&views);
+    let mut runtime = Runtime::new(
+        app::init,
+        app::update,
+        saucer_core::no_subscriptions(),
+        |model: &app::Model| model.count,
+        move |count: &u32, _: &SendToManager<CoreManager, runtime::SelfMsg>| {
+            rendered.lock().unwrap().push(*count)
+        },
+        saucer_core::no_op_observer(),
+    );
+    let debugger = runtime.debugger(3);
+    let ports = runtime.ports();
+    ports.set_count.send(1);
+    ports.increment_port.send();
+    ports.increment_port.send();
+
+    let last_view = || *views.lock().unwrap().last().unwrap();
+    let drive = async {
+        until(|| debugger.history().last().map(|e| e.model.count) == Some(3)).await;
+        // init's model has already been dropped to stay within capacity.
+        let history = debugger.history();
+        let counts: Vec<u32> = history.iter().map(|e| e.model.count).collect();
+        assert_eq!(counts, [1, 2, 3]);
+        assert!(matches!(history[0].msg, Some(app::Msg::Set(1))));
+
+        debugger.jump_to(0);
+        until(|| last_view() == 1).await;
+        ports.increment_port.send();
+        // The runtime only yields once nothing it may handle is ready, so
+        // when this second jump renders, the held message has had its chance.
+        let rendered = views.lock().unwrap().len();
+        debugger.jump_to(1);
+        until(|| views.lock().unwrap().len() > rendered).await;
+        assert_eq!(last_view(), 2);
+        assert_eq!(debugger.history().len(), 3);
+        assert_eq!(debugger.history()[2].model.count, 3);
+
+        debugger.resume();
+        until(|| debugger.history()[2].model.count == 4).await;
+        assert!(views.lock().unwrap().ends_with(&[1, 2, 3, 4]));
+        ports.set_count.send(5);
+    };
+
+    rt.block_on(async {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::join!(runtime.run(), drive);
+        })
+        .await
+        .expect("runtime should complete");
+    });
+}
@@ -0,0 +1,2 @@
+// commands are generated; see requests.rs for real helpers and EchoRequest definitions.
+// This file stays empty as a breadcrumb for fictional imports.
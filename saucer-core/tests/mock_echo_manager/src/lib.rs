@@ -0,0 +1,48 @@
+pub mod requests;
+pub use requests::{echo, echo_later, EchoRequest};
+use saucer_core::{EffectManager, Router};
+
+// === Effect Manager ===
+
+pub struct EchoManager;
+
+impl EffectManager for EchoManager {
+    /// `EchoLater` replies waiting for the manager's self-message.
+    type State<Msg> = Vec<Msg>;
+    type Request<Msg> = EchoRequest<Msg>;
+    type SelfMsg = ();
+
+    fn init<Msg: Send + 'static>() -> Vec<Msg> {
+        Vec::new()
+    }
+
+    fn on_effects<Msg: Send + 'static>(
+        &self,
+        router: &Router<Msg, ()>,
+        mut state: Vec<Msg>,
+        effects: Vec<EchoRequest<Msg>>,
+    ) -> Vec<Msg> {
+        for req in effects {
+            match req {
+                EchoRequest::Echo { value, returns } => router.send_to_app(returns(value)),
+                EchoRequest::EchoLater { value, returns } => {
+                    state.push(returns(value));
+                    router.send_to_self(());
+                }
+            }
+        }
+        state
+    }
+
+    fn on_self_msg<Msg: Send + 'static>(
+        &self,
+        router: &Router<Msg, ()>,
+        mut state: Vec<Msg>,
+        _msg: (),
+    ) -> Vec<Msg> {
+        for reply in state.drain(..) {
+            router.send_to_app(reply);
+        }
+        state
+    }
+}
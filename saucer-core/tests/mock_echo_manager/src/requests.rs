@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub enum EchoRequest<Msg> {
+    Echo {
+        value: String,
+        returns: Arc<dyn Fn(String) -> Msg + Send + Sync>,
+    },
+    /// Like `Echo`, but answered from `on_self_msg` after the manager sends
+    /// itself a `()`.
+    EchoLater {
+        value: String,
+        returns: Arc<dyn Fn(String) -> Msg + Send + Sync>,
+    },
+}
+
+impl<Msg: 'static> EchoRequest<Msg> {
+    pub fn map<Msg2>(
+        self,
+        f: impl Fn(Msg) -> Msg2 + Send + Sync + Clone + 'static,
+    ) -> EchoRequest<Msg2> {
+        match self {
+            EchoRequest::Echo { value, returns } => EchoRequest::Echo {
+                value,
+                returns: Arc::new(move |v| f(returns(v))),
+            },
+            EchoRequest::EchoLater { value, returns } => EchoRequest::EchoLater {
+                value,
+                returns: Arc::new(move |v| f(returns(v))),
+            },
+        }
+    }
+}
+
+impl<Msg> std::fmt::Debug for EchoRequest<Msg> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EchoRequest::Echo { value, .. } => f
+                .debug_struct("EchoRequest::Echo")
+                .field("value", value)
+                .finish(),
+            EchoRequest::EchoLater { value, .. } => f
+                .debug_struct("EchoRequest::EchoLater")
+                .field("value", value)
+                .finish(),
+        }
+    }
+}
+
+impl<Msg> saucer_core::RequestFields for EchoRequest<Msg> {
+    fn variant(&self) -> &'static str {
+        match self {
+            EchoRequest::Echo { .. } => "Echo",
+            EchoRequest::EchoLater { .. } => "EchoLater",
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        match self {
+            EchoRequest::Echo { value, .. } | EchoRequest::EchoLater { value, .. } => {
+                vec![("value", format!("{:?}", value))]
+            }
+        }
+    }
+}
+
+impl<Msg> saucer_core::Resolve<String, Msg> for EchoRequest<Msg> {
+    fn resolve(&self, result: String) -> Option<Msg> {
+        match self {
+            EchoRequest::Echo { returns, .. } | EchoRequest::EchoLater { returns, .. } => {
+                Some(returns(result))
+            }
+        }
+    }
+}
+
+pub fn echo<Msg: 'static>(
+    value: String,
+    returns: impl Fn(String) -> Msg + Send + Sync + 'static,
+) -> EchoRequest<Msg> {
+    EchoRequest::Echo {
+        value,
+        returns: Arc::new(returns),
+    }
+}
+
+pub fn echo_later<Msg: 'static>(
+    value: String,
+    returns: impl Fn(String) -> Msg + Send + Sync + 'static,
+) -> EchoRequest<Msg> {
+    EchoRequest::EchoLater {
+        value,
+        returns: Arc::new(returns),
+    }
+}
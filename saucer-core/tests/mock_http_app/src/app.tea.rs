@@ -0,0 +1,41 @@
+use saucer_core::Cmd;
+use saucer_core::command::shutdown;
+use saucer_http_manager::command::request;
+use saucer_http_manager::{expect_string, HttpError, HttpMethod};
+
+use std::time::Duration;
+
+pub struct Model {
+    pub reply: Option<Result<String, HttpError>>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Msg {
+    GotReply(Result<String, HttpError>),
+}
+
+pub fn init(url: String) -> (Model, Cmd<Msg>) {
+    let cmd = request(
+        HttpMethod::Put,
+        url,
+        vec![("Authorization".to_string(), "Bearer t0k3n".to_string())],
+        Some(b"ping".to_vec()),
+        Some(Duration::from_secs(5)),
+        None,
+        expect_string(Msg::GotReply),
+    );
+    (Model { reply: None }, cmd)
+}
+
+pub fn update(mut model: Model, msg: Msg) -> (Model, Cmd<Msg>) {
+    match msg {
+        Msg::GotReply(reply) => {
+            model.reply = Some(reply);
+            (model, shutdown())
+        }
+    }
+}
+
+pub fn view(_m: &Model) -> () {
+    ()
+}
@@ -0,0 +1,81 @@
+pub mod runtime {
+    include!(concat!(env!("OUT_DIR"), "/runtime.rs"));
+}
+
+pub use runtime::mock_http_app::app;
+use runtime::sync::Runtime;
+use saucer_core::{Observation, ObserverFn};
+use saucer_http_manager::HttpError;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::runtime::Builder;
+
+const PONG: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\npong";
+
+/// Run the app against a one-shot loopback server. Returns the app's decoded
+/// reply and the raw request the server saw.
+pub fn run_app() -> (Result<String, HttpError>, String) {
+    let rt = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("tokio runtime");
+
+    let reply = Arc::new(Mutex::new(None));
+    let seen = Arc::clone(&reply);
+    let observer: ObserverFn<app::Msg, runtime::Request<app::Msg>, runtime::SelfMsg> =
+        Arc::new(move |observation| {
+            if let Observation::Event {
+                data: app::Msg::GotReply(result),
+                ..
+            } = observation
+            {
+                *seen.lock().unwrap() = Some(result.clone());
+            }
+        });
+
+    // Bind up front so the URL is known when the runtime is built.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+    listener.set_nonblocking(true).expect("nonblocking");
+    let url = format!("http://{}/echo", listener.local_addr().expect("addr"));
+
+    let runtime = Runtime::new(
+        move || app::init(url),
+        app::update,
+        saucer_core::no_subscriptions(),
+        app::view,
+        saucer_core::no_op_reconciler(),
+        observer,
+    );
+
+    let raw_request = rt.block_on(async {
+        let listener = TcpListener::from_std(listener).expect("tokio listener");
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.expect("accept");
+            let mut raw = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !raw.ends_with(b"ping") {
+                let n = socket.read(&mut buf).await.expect("read");
+                if n == 0 {
+                    break;
+                }
+                raw.extend_from_slice(&buf[..n]);
+            }
+            socket.write_all(PONG).await.expect("write");
+            String::from_utf8_lossy(&raw).into_owned()
+        });
+
+        tokio::time::timeout(Duration::from_secs(5), runtime.run())
+            .await
+            .expect("runtime should complete before timeout");
+        server.await.expect("server task")
+    });
+
+    let reply = reply
+        .lock()
+        .unwrap()
+        .take()
+        .expect("app should get a reply");
+    (reply, raw_request)
+}
@@ -1,25 +1,29 @@
-// Contrived mock to exercise saucer-core wiring; expected to change once a real
-// saucer-http-manager with Elm-like `expect` APIs lands.
+// Contrived mock to exercise saucer-core wiring: reuses saucer-http-manager's
+// request types but answers each request locally with its method and URL.
 pub mod requests;
 pub use requests::get;
-use saucer_core::Router;
+use saucer_core::{EffectManager, Router};
 pub use saucer_http_manager::{HttpError, HttpMethod, HttpRequest, HttpResponse};
 
 // === Effect Manager ===
 
 pub struct HttpManager;
 
-impl HttpManager {
-    pub fn init() -> () {
-        ()
-    }
+impl EffectManager for HttpManager {
+    type State<Msg> = ();
+    type Request<Msg> = HttpRequest<Msg>;
+    type SelfMsg = ();
+
+    fn init<Msg: Send + 'static>() {}
 
-    pub fn on_effects<Msg: Send + 'static>(
+    fn on_effects<Msg: Send + 'static>(
         &self,
         router: &Router<Msg, ()>,
         state: (),
         effects: Vec<HttpRequest<Msg>>,
     ) -> () {
+        // Echo the batch size so runtime tests can see how requests were grouped.
+        let batch = effects.len();
         for req in effects {
             let HttpRequest {
                 method,
@@ -27,10 +31,16 @@ impl HttpManager {
                 returns,
                 ..
             } = req;
-            let body = match method {
-                HttpMethod::Get => format!("GET {}", url),
-                HttpMethod::Post => format!("POST {}", url),
+            let verb = match method {
+                HttpMethod::Get => "GET",
+                HttpMethod::Post => "POST",
+                HttpMethod::Put => "PUT",
+                HttpMethod::Patch => "PATCH",
+                HttpMethod::Delete => "DELETE",
+                HttpMethod::Head => "HEAD",
+                HttpMethod::Options => "OPTIONS",
             };
+            let body = format!("{} {} (batch of {})", verb, url, batch);
             let resp = HttpResponse {
                 status: 200,
                 headers: vec![],
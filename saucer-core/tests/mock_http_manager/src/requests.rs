@@ -1,8 +1,17 @@
-use saucer_http_manager::{HttpError, HttpRequest, HttpResponse};
+use saucer_http_manager::{HttpError, HttpMethod, HttpRequest, HttpResponse};
+use std::sync::Arc;
 
 pub fn get<Msg: 'static>(
     url: impl Into<String>,
     returns: impl Fn(Result<HttpResponse, HttpError>) -> Msg + Send + Sync + 'static,
 ) -> HttpRequest<Msg> {
-    saucer_http_manager::requests::get(url, returns)
+    HttpRequest {
+        method: HttpMethod::Get,
+        url: url.into(),
+        headers: Vec::new(),
+        body: None,
+        timeout: None,
+        tracker: None,
+        returns: Arc::new(returns),
+    }
 }
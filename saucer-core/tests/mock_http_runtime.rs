@@ -0,0 +1,10 @@
+// `command::request` flows through codegen into the real HTTP manager.
+#[test]
+fn mock_http_runtime_sends_request_with_headers() {
+    let (reply, raw_request) = mock_http_app::run_app();
+    assert_eq!(reply.expect("reply"), "pong");
+    assert!(raw_request.starts_with("PUT /echo HTTP/1.1\r\n"));
+    assert!(raw_request
+        .to_ascii_lowercase()
+        .contains("authorization: bearer t0k3n\r\n"));
+}
@@ -0,0 +1,25 @@
+//! Asks for the time once, then shuts down.
+
+use mock_time_manager::{command::time_now, Posix};
+use saucer_core::command::shutdown;
+use saucer_core::Cmd;
+
+/// Nothing to keep: the app stops after its first message.
+pub struct Model {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Msg {
+    GotTime(Posix),
+}
+
+pub fn init() -> (Model, Cmd<Msg>) {
+    (Model {}, time_now(Msg::GotTime))
+}
+
+pub fn update(model: Model, msg: Msg) -> (Model, Cmd<Msg>) {
+    match msg {
+        Msg::GotTime(_) => (model, shutdown()),
+    }
+}
+
+pub fn view(_m: &Model) {}
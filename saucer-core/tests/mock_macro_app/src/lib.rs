@@ -0,0 +1,48 @@
+pub mod runtime {
+    saucer_core::runtime! {
+        app = app,
+        managers = [mock_time_manager],
+    }
+}
+
+pub use runtime::mock_macro_app::app;
+use runtime::sync::Runtime;
+use saucer_core::{Observation, ObserverFn};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::Builder;
+
+/// Run the app to shutdown and return the messages `update` saw.
+pub fn run_app() -> Vec<app::Msg> {
+    let rt = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("tokio runtime");
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&events);
+    let observer: ObserverFn<app::Msg, runtime::Request<app::Msg>, runtime::SelfMsg> =
+        Arc::new(move |observation| {
+            if let Observation::Event { data, .. } = observation {
+                seen.lock().unwrap().push(data.clone());
+            }
+        });
+
+    let runtime = Runtime::new(
+        app::init,
+        app::update,
+        saucer_core::no_subscriptions(),
+        app::view,
+        saucer_core::no_op_reconciler(),
+        observer,
+    );
+
+    rt.block_on(async {
+        tokio::time::timeout(Duration::from_secs(1), runtime.run())
+            .await
+            .expect("runtime should complete before timeout")
+    });
+
+    let events = events.lock().unwrap().clone();
+    events
+}
@@ -0,0 +1,9 @@
+use mock_macro_app::{app, run_app};
+
+// `runtime!` runs the same codegen as a build script, so the app runs the
+// same way without one.
+#[test]
+fn macro_runtime_runs_the_app() {
+    let events = run_app();
+    assert!(matches!(events[..], [app::Msg::GotTime(_)]), "{:?}", events);
+}
@@ -0,0 +1,22 @@
+use saucer_core::build::{ManagerConfig, RuntimeBuilder};
+
+fn main() {
+    // Two runtimes in one crate, each with its own host, templates and output.
+    RuntimeBuilder::new()
+        .host("src/cli.rs")
+        .template_root("src/cli")
+        .output("cli_runtime.rs")
+        .generate();
+
+    // mock-echo-manager has no saucer metadata; the harness declares it here.
+    RuntimeBuilder::new()
+        .host("src/harness.rs")
+        .template_root("src/harness")
+        .manager(ManagerConfig::new(
+            "mock-echo-manager",
+            "EchoRequest",
+            "EchoManager",
+        ))
+        .output("harness_runtime.rs")
+        .generate();
+}
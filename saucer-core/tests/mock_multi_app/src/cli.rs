@@ -0,0 +1,45 @@
+pub mod runtime {
+    include!(concat!(env!("OUT_DIR"), "/cli_runtime.rs"));
+}
+
+pub use runtime::mock_multi_app::counter;
+use runtime::sync::Runtime;
+use saucer_core::{Observation, ObserverFn};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::Builder;
+
+/// Run the counter to shutdown and return the messages `update` saw.
+pub fn run_counter() -> Vec<counter::Msg> {
+    let rt = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("tokio runtime");
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&events);
+    let observer: ObserverFn<counter::Msg, runtime::Request<counter::Msg>, runtime::SelfMsg> =
+        Arc::new(move |observation| {
+            if let Observation::Event { data, .. } = observation {
+                seen.lock().unwrap().push(data.clone());
+            }
+        });
+
+    let runtime = Runtime::new(
+        counter::init,
+        counter::update,
+        saucer_core::no_subscriptions(),
+        counter::view,
+        saucer_core::no_op_reconciler(),
+        observer,
+    );
+
+    rt.block_on(async {
+        tokio::time::timeout(Duration::from_secs(1), runtime.run())
+            .await
+            .expect("runtime should complete before timeout")
+    });
+
+    let events = events.lock().unwrap().clone();
+    events
+}
@@ -0,0 +1,37 @@
+use mock_time_manager::command::notify_after;
+use saucer_core::command::shutdown;
+use saucer_core::Cmd;
+
+use std::time::Duration;
+
+pub struct Model {
+    pub count: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Msg {
+    Tick,
+}
+
+pub fn init() -> (Model, Cmd<Msg>) {
+    (
+        Model { count: 0 },
+        notify_after(1, Duration::from_millis(0), || Msg::Tick),
+    )
+}
+
+pub fn update(mut model: Model, msg: Msg) -> (Model, Cmd<Msg>) {
+    match msg {
+        Msg::Tick => {
+            model.count += 1;
+            if model.count == 3 {
+                (model, shutdown())
+            } else {
+                let next = notify_after(1, Duration::from_millis(0), || Msg::Tick);
+                (model, next)
+            }
+        }
+    }
+}
+
+pub fn view(_m: &Model) {}
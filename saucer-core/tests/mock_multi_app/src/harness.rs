@@ -0,0 +1,74 @@
+pub mod runtime {
+    include!(concat!(env!("OUT_DIR"), "/harness_runtime.rs"));
+}
+
+pub use runtime::mock_multi_app::{deferred, echo};
+use runtime::sync::Runtime;
+use saucer_core::{Observation, ObserverFn};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::Builder;
+
+/// Run the echo app to shutdown and return the messages `update` saw.
+pub fn run_echo() -> Vec<echo::Msg> {
+    let (observer, events) = recorder();
+    let runtime = Runtime::new(
+        echo::init,
+        echo::update,
+        saucer_core::no_subscriptions(),
+        echo::view,
+        saucer_core::no_op_reconciler(),
+        observer,
+    );
+    run_to_shutdown(runtime.run());
+    let events = events.lock().unwrap().clone();
+    events
+}
+
+/// Run the app whose echo only comes back through the manager's
+/// self-message, returning the messages `update` saw.
+pub fn run_deferred() -> Vec<deferred::Msg> {
+    let (observer, events) = recorder();
+    let runtime = Runtime::new(
+        deferred::init,
+        deferred::update,
+        saucer_core::no_subscriptions(),
+        deferred::view,
+        saucer_core::no_op_reconciler(),
+        observer,
+    );
+    run_to_shutdown(runtime.run());
+    let events = events.lock().unwrap().clone();
+    events
+}
+
+type Recorded<Msg> = Arc<Mutex<Vec<Msg>>>;
+
+fn recorder<Msg: Clone + Send + 'static>() -> (
+    ObserverFn<Msg, runtime::Request<Msg>, runtime::SelfMsg>,
+    Recorded<Msg>,
+) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&events);
+    let observer: ObserverFn<Msg, runtime::Request<Msg>, runtime::SelfMsg> =
+        Arc::new(move |observation| {
+            if let Observation::Event { data, .. } = observation {
+                seen.lock().unwrap().push(data.clone());
+            }
+        });
+    (observer, events)
+}
+
+fn run_to_shutdown(run: impl Future<Output = ()>) {
+    let rt = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("tokio runtime");
+
+    rt.block_on(async {
+        tokio::time::timeout(Duration::from_secs(1), run)
+            .await
+            .expect("runtime should complete before timeout")
+    });
+}
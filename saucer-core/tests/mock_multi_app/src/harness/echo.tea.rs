@@ -0,0 +1,22 @@
+use mock_echo_manager::command::echo;
+use saucer_core::command::shutdown;
+use saucer_core::Cmd;
+
+pub struct Model {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Msg {
+    Echoed(String),
+}
+
+pub fn init() -> (Model, Cmd<Msg>) {
+    (Model {}, echo("ping".to_string(), Msg::Echoed))
+}
+
+pub fn update(model: Model, msg: Msg) -> (Model, Cmd<Msg>) {
+    match msg {
+        Msg::Echoed(_) => (model, shutdown()),
+    }
+}
+
+pub fn view(_m: &Model) {}
@@ -0,0 +1,27 @@
+use mock_multi_app::{cli, harness};
+
+// One crate, two runtimes: each host includes its own output and sees only
+// its own templates and managers.
+#[test]
+fn cli_runtime_runs_the_counter() {
+    assert_eq!(cli::run_counter(), vec![cli::counter::Msg::Tick; 3]);
+}
+
+// mock-echo-manager has no saucer metadata; the build script declares it.
+#[test]
+fn harness_runtime_runs_with_a_configured_manager() {
+    assert_eq!(
+        harness::run_echo(),
+        vec![harness::echo::Msg::Echoed("ping".to_string())]
+    );
+}
+
+// The echo manager's `SelfMsg` is `()`; its self-message must still reach
+// `on_self_msg`, which is the only place the reply is sent from.
+#[test]
+fn harness_runtime_delivers_unit_self_messages() {
+    assert_eq!(
+        harness::run_deferred(),
+        vec![harness::deferred::Msg::Echoed("pong".to_string())]
+    );
+}
@@ -1,6 +1,7 @@
 use saucer_core::{Cmd, Sub};
 use saucer_core::command::shutdown;
 
+#[derive(Clone)]
 pub struct Model { pub count: u32 }
 #[derive(Clone, Debug)]
 pub enum Msg { Increment, Set(u32) }
@@ -9,6 +9,7 @@ use runtime::sync::Runtime;
 pub fn build_runtime() -> Runtime<
     impl FnOnce() -> (app::Model, runtime::Cmd<app::Msg>),
     impl Fn(app::Model, app::Msg) -> (app::Model, runtime::Cmd<app::Msg>),
+    impl Fn(&app::Model) -> runtime::Sub<app::Msg>,
     impl Fn(&app::Model) -> (),
     impl FnMut(&(), &saucer_core::SendToManager<saucer_core::CoreManager, runtime::SelfMsg>),
     app::Model,
@@ -17,6 +18,7 @@ pub fn build_runtime() -> Runtime<
     Runtime::new(
         app::init,
         app::update,
+        saucer_core::no_subscriptions(),
         app::view,
         saucer_core::no_op_reconciler(),
         saucer_core::tracing_observer(),
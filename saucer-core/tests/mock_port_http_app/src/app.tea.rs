@@ -32,7 +32,7 @@ pub fn update(mut model: Model, msg: Msg) -> (Model, Cmd<Msg>) {
             let cmd = get("/ping", |resp| {
                 let body = match resp {
                     Ok(r) => String::from_utf8_lossy(&r.body).to_string(),
-                    Err(e) => format!("err: {}", e.message),
+                    Err(e) => format!("err: {}", e),
                 };
                 Msg::Got(body)
             });
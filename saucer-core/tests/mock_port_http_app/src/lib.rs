@@ -21,6 +21,7 @@ pub fn run_app() {
     let runtime = Runtime::new(
         app::init,
         app::update,
+        saucer_core::no_subscriptions(),
         app::view,
         saucer_core::no_op_reconciler(),
         saucer_core::tracing_observer(),
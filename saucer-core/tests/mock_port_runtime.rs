@@ -36,3 +36,34 @@ fn mock_port_app_runs_and_emits_counts() {
 
     assert_eq!(&*seen.lock().unwrap(), &[2, 3, 4, 5]);
 }
+
+mod port_attribute {
+    use saucer_core::port;
+
+    #[derive(Debug, PartialEq)]
+    pub enum Msg {
+        Set(u32),
+    }
+
+    #[port]
+    pub fn set(value: u32) -> Sub<Msg> {
+        Msg::Set(value)
+    }
+
+    // Removed by the attribute, or this module would define `report` twice.
+    #[port]
+    pub fn report(_value: u32) -> Cmd<Msg> {
+        unreachable!()
+    }
+
+    pub fn report(value: u32) -> u32 {
+        value
+    }
+}
+
+// Editors expand `#[port]` the way the build transform rewrites ports.
+#[test]
+fn port_attribute_mirrors_the_template_rewrite() {
+    assert_eq!(port_attribute::set(4), port_attribute::Msg::Set(4));
+    assert_eq!(port_attribute::report(4), 4);
+}
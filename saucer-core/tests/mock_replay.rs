@@ -0,0 +1,72 @@
+use mock_app::app;
+use saucer_core::{jsonl_observer, replay, ReplayError};
+use std::path::PathBuf;
+
+fn log_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("saucer-replay-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+// The timestamp in `GotTime` comes from the wall clock, so only a replay of
+// the recorded messages can land on the same URLs and final model.
+#[test]
+fn replay_rebuilds_the_recorded_model() {
+    let dir = log_dir("roundtrip");
+    mock_app::run_app_with(jsonl_observer(&dir));
+    // A second session appended to the same log; replay takes the latest.
+    mock_app::run_app_with(jsonl_observer(&dir));
+
+    let log = std::fs::read_to_string(dir.join("events.jsonl")).unwrap();
+    assert_eq!(log.matches(r#""type":"start""#).count(), 2);
+    assert!(log.contains(r#""variant":"NotifyAfter""#), "{}", log);
+
+    let model = replay(dir.join("events.jsonl"), app::init, app::update).unwrap();
+    let last_time = log
+        .lines()
+        .rev()
+        .find_map(|line| {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            record["data"]["GotTime"].as_str().map(str::to_owned)
+        })
+        .unwrap();
+    assert_eq!(model.req_time, Some(last_time));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn replay_fails_on_the_first_diverging_effect() {
+    let dir = log_dir("diverged");
+    std::fs::create_dir_all(&dir).unwrap();
+    let log = dir.join("events.jsonl");
+    std::fs::write(
+        &log,
+        [
+            r#"{"type":"start","ts":0.0}"#,
+            r#"{"type":"effect","ts":0.0,"data":{"manager":"mock_time_manager","variant":"NotifyAfter","fields":{"id":"0","duration":"0ns"}}}"#,
+            r#"{"type":"event","ts":0.0,"data":{"GotTime":"noon"}}"#,
+            r#"{"type":"effect","ts":0.0,"data":{"manager":"mock_http_manager","variant":"HttpRequest","fields":{"url":"\"https://api.example.com/data?ts=midnight\""}}}"#,
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+
+    let Err(error) = replay(&log, app::init, app::update) else {
+        panic!("replay should diverge");
+    };
+    assert!(
+        matches!(
+            error,
+            ReplayError::Diverged {
+                line: 4,
+                cause: Some(3),
+                ..
+            }
+        ),
+        "{:?}",
+        error
+    );
+    let message = error.to_string();
+    assert!(message.contains("ts=noon"), "{}", message);
+    std::fs::remove_dir_all(dir).unwrap();
+}
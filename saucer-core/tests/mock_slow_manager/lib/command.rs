@@ -0,0 +1,2 @@
+// commands are generated; see requests.rs for the real helpers.
+// This file stays empty as a breadcrumb for fictional imports.
@@ -0,0 +1,12 @@
+use crate::SlowRequest;
+use std::sync::Arc;
+
+pub fn crunch<Msg: 'static>(
+    millis: u64,
+    returns: impl Fn() -> Msg + Send + Sync + 'static,
+) -> SlowRequest<Msg> {
+    SlowRequest::Crunch {
+        millis,
+        returns: Arc::new(returns),
+    }
+}
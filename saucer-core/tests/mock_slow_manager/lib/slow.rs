@@ -0,0 +1,70 @@
+// Blocking mock manager: declares `spawn = true` so its `on_effects` runs off
+// the runtime loop.
+pub mod requests;
+pub use requests::crunch;
+use saucer_core::{EffectManager, Router};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub enum SlowRequest<Msg> {
+    /// Block the calling thread for `millis`, then reply.
+    Crunch {
+        millis: u64,
+        returns: Arc<dyn Fn() -> Msg + Send + Sync>,
+    },
+}
+
+impl<Msg: 'static> SlowRequest<Msg> {
+    pub fn map<Msg2>(
+        self,
+        f: impl Fn(Msg) -> Msg2 + Send + Sync + Clone + 'static,
+    ) -> SlowRequest<Msg2> {
+        match self {
+            SlowRequest::Crunch { millis, returns } => SlowRequest::Crunch {
+                millis,
+                returns: Arc::new(move || f(returns())),
+            },
+        }
+    }
+}
+
+// Deliberately no `RequestFields`: apps using this manager still build,
+// their `Request` enum just doesn't implement `Describe`.
+impl<Msg> std::fmt::Debug for SlowRequest<Msg> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SlowRequest::Crunch { millis, .. } => {
+                f.debug_struct("Crunch").field("millis", millis).finish()
+            }
+        }
+    }
+}
+
+// === Effect Manager ===
+
+pub struct SlowManager;
+
+impl EffectManager for SlowManager {
+    type State<Msg> = ();
+    type Request<Msg> = SlowRequest<Msg>;
+    type SelfMsg = ();
+
+    fn init<Msg: Send + 'static>() {}
+
+    fn on_effects<Msg: Send + 'static>(
+        &self,
+        router: &Router<Msg, ()>,
+        state: (),
+        effects: Vec<SlowRequest<Msg>>,
+    ) {
+        for req in effects {
+            match req {
+                SlowRequest::Crunch { millis, returns } => {
+                    std::thread::sleep(std::time::Duration::from_millis(millis));
+                    router.send_to_app(returns());
+                }
+            }
+        }
+        state
+    }
+}
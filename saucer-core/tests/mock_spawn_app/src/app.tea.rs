@@ -0,0 +1,34 @@
+use saucer_core::Cmd;
+use saucer_core::command::shutdown;
+use slow_manager::command::crunch;
+use mock_time_manager::command::notify_after;
+
+use std::time::Duration;
+
+pub struct Model {}
+
+#[derive(Clone, Debug)]
+pub enum Msg {
+    Ticked,
+    Crunched,
+}
+
+pub fn init() -> (Model, Cmd<Msg>) {
+    // The slow manager blocks for a while; the timer should not wait for it.
+    let cmd = Cmd::batch([
+        crunch(300, || Msg::Crunched),
+        notify_after(1, Duration::from_millis(0), || Msg::Ticked),
+    ]);
+    (Model {}, cmd)
+}
+
+pub fn update(model: Model, msg: Msg) -> (Model, Cmd<Msg>) {
+    match msg {
+        Msg::Ticked => (model, Cmd::none()),
+        Msg::Crunched => (model, shutdown()),
+    }
+}
+
+pub fn view(_m: &Model) -> () {
+    ()
+}
@@ -0,0 +1,47 @@
+pub mod runtime {
+    include!(concat!(env!("OUT_DIR"), "/runtime.rs"));
+}
+
+pub use runtime::mock_spawn_app::app;
+use runtime::sync::Runtime;
+use saucer_core::{Observation, ObserverFn};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::runtime::Builder;
+
+/// Run the app to completion and return each message it received together
+/// with how long after startup `update` saw it.
+pub fn run_app() -> Vec<(app::Msg, Duration)> {
+    let rt = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("tokio runtime");
+
+    let started = Instant::now();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&events);
+    let observer: ObserverFn<app::Msg, runtime::Request<app::Msg>, runtime::SelfMsg> =
+        Arc::new(move |observation| {
+            if let Observation::Event { data, .. } = observation {
+                seen.lock().unwrap().push((data.clone(), started.elapsed()));
+            }
+        });
+
+    let runtime = Runtime::new(
+        app::init,
+        app::update,
+        saucer_core::no_subscriptions(),
+        app::view,
+        saucer_core::no_op_reconciler(),
+        observer,
+    );
+
+    rt.block_on(async {
+        tokio::time::timeout(Duration::from_secs(5), runtime.run())
+            .await
+            .expect("runtime should complete before timeout")
+    });
+
+    let events = events.lock().unwrap().clone();
+    events
+}
@@ -0,0 +1,19 @@
+use mock_spawn_app::app::Msg;
+use std::time::Duration;
+
+// The slow manager runs on its own task, so the timer reply reaches `update`
+// while it is still blocked.
+#[test]
+fn mock_spawn_runtime_keeps_updating_while_a_spawned_manager_blocks() {
+    let events = mock_spawn_app::run_app();
+    assert_eq!(events.len(), 2, "unexpected events: {:?}", events);
+
+    let (first, _) = &events[0];
+    let (second, crunched_at) = &events[1];
+    assert!(
+        matches!(first, Msg::Ticked),
+        "timer waited for the slow manager"
+    );
+    assert!(matches!(second, Msg::Crunched));
+    assert!(*crunched_at >= Duration::from_millis(300));
+}
@@ -0,0 +1,3 @@
+fn main() {
+    saucer_core::build::generate_runtime();
+}
@@ -0,0 +1,99 @@
+pub mod runtime {
+    include!(concat!(env!("OUT_DIR"), "/runtime.rs"));
+}
+
+/// The generated runtime, for checking what editors are shown.
+pub const RUNTIME_SOURCE: &str = include_str!(concat!(env!("OUT_DIR"), "/runtime.rs"));
+
+pub use runtime::mock_sub_app::app;
+use runtime::sync::Runtime;
+use saucer_core::{Observation, ObserverFn};
+use saucer_time_manager::{Clock, Posix, TimerState};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::Builder;
+
+/// Run the app to completion and return how many ticks reached `update`.
+pub fn run_app() -> usize {
+    let rt = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("tokio runtime");
+
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::clone(&ticks);
+    let observer: ObserverFn<app::Msg, runtime::Request<app::Msg>, runtime::SelfMsg> =
+        Arc::new(move |observation| {
+            if let Observation::Event {
+                data: app::Msg::Tick(_),
+                ..
+            } = observation
+            {
+                seen.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+    let runtime = Runtime::new(
+        app::init,
+        app::update,
+        app::subscriptions,
+        app::view,
+        saucer_core::no_op_reconciler(),
+        observer,
+    );
+
+    rt.block_on(async {
+        // Virtual time keeps the tick count deterministic.
+        tokio::time::pause();
+        tokio::time::timeout(Duration::from_secs(1), runtime.run())
+            .await
+            .expect("runtime should complete before timeout")
+    });
+
+    ticks.load(Ordering::SeqCst)
+}
+
+/// Run the app with the time manager on a virtual clock starting at
+/// `origin` (Unix millis), and return the times its ticks carried.
+pub fn run_app_on_virtual_clock(origin: i64) -> Vec<i64> {
+    let rt = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("tokio runtime");
+
+    let ticks = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&ticks);
+    let observer: ObserverFn<app::Msg, runtime::Request<app::Msg>, runtime::SelfMsg> =
+        Arc::new(move |observation| {
+            if let Observation::Event {
+                data: app::Msg::Tick(at),
+                ..
+            } = observation
+            {
+                seen.lock().unwrap().push(at.millis());
+            }
+        });
+
+    let mut runtime = Runtime::new(
+        app::init,
+        app::update,
+        app::subscriptions,
+        app::view,
+        saucer_core::no_op_reconciler(),
+        observer,
+    );
+    runtime.init_time_state(move || {
+        TimerState::with_clock(Clock::virtual_at(Posix::from_millis(origin)))
+    });
+
+    rt.block_on(async {
+        tokio::time::pause();
+        tokio::time::timeout(Duration::from_secs(1), runtime.run())
+            .await
+            .expect("runtime should complete before timeout")
+    });
+
+    let ticks = ticks.lock().unwrap().clone();
+    ticks
+}
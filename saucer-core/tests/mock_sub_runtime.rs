@@ -0,0 +1,57 @@
+// Subscriptions are re-evaluated after each update; dropping `every` stops the ticks.
+#[test]
+fn mock_sub_runtime_stops_ticking_after_unsubscribe() {
+    assert_eq!(mock_sub_app::run_app(), 3);
+}
+
+// An app can start the time manager on a virtual clock; under
+// `tokio::time::pause` each tick then reads exactly one interval later.
+#[test]
+fn mock_sub_runtime_ticks_on_a_virtual_clock() {
+    let origin = 1_700_000_000_000;
+    let ticks = mock_sub_app::run_app_on_virtual_clock(origin);
+    let offsets: Vec<i64> = ticks.iter().map(|at| at - origin).collect();
+    assert_eq!(offsets, vec![10, 20, 30]);
+}
+
+// Templates are included from their own file with lines left in place, so
+// compiler locations match the .tea.rs being edited.
+#[test]
+fn template_code_keeps_its_file_name_and_line() {
+    let (file, line) = mock_sub_app::app::here();
+    assert!(
+        file.ends_with("templates/mock_sub_app/app.tea.rs"),
+        "{}",
+        file
+    );
+
+    let template = include_str!("mock_sub_app/src/app.tea.rs");
+    let expected = template
+        .lines()
+        .position(|l| l.contains("line!()"))
+        .expect("template calls line!()")
+        + 1;
+    assert_eq!(line as usize, expected);
+}
+
+// rust-analyzer reads the template as written, with its fictional imports
+// resolved through shadow modules.
+#[test]
+fn editors_see_the_template_as_written() {
+    let source = mock_sub_app::RUNTIME_SOURCE;
+    let lines: Vec<&str> = source.lines().map(str::trim).collect();
+    let include = lines
+        .iter()
+        .position(|l| {
+            l.starts_with("include!(\"") && l.ends_with("mock_sub_app/src/app.tea.rs\");")
+        })
+        .expect("original template is included");
+    assert_eq!(lines[include - 1], "#[cfg(rust_analyzer)]");
+
+    let shadow = lines
+        .iter()
+        .position(|l| *l == "mod saucer_time_manager {")
+        .expect("manager crate is shadowed");
+    assert_eq!(lines[shadow + 1], "pub use ::saucer_time_manager::*;");
+    assert_eq!(lines[shadow + 2], "pub mod command {");
+}
@@ -0,0 +1,89 @@
+use mock_batch_app::app::{self, Msg};
+use mock_batch_app::runtime::sync::TestRuntime;
+use mock_batch_app::runtime::Request;
+use mock_http_manager::{HttpRequest, HttpResponse};
+use mock_time_manager::TimeRequest;
+
+fn ok(body: &str) -> Result<HttpResponse, mock_http_manager::HttpError> {
+    Ok(HttpResponse {
+        status: 200,
+        headers: Vec::new(),
+        body: body.as_bytes().to_vec(),
+    })
+}
+
+// Nothing runs until the test resolves a request and steps, so every
+// intermediate state can be asserted on.
+#[test]
+fn test_runtime_drives_update_through_resolved_requests() {
+    let mut rt = TestRuntime::new(app::init, app::update);
+    assert!(rt.model().req_time.is_none());
+
+    let pending = rt.pending_requests();
+    let [Request::Time(TimeRequest::NotifyAfter { .. })] = &pending[..] else {
+        panic!("expected one timer, got {:?}", pending);
+    };
+    rt.resolve::<TimeRequest<_>, _>(0, ());
+    assert!(rt.pending_requests().is_empty());
+    assert!(rt.step());
+    assert!(!rt.step());
+    assert!(rt.model().req_time.is_some());
+
+    let pending = rt.pending_requests();
+    let urls: Vec<&str> = pending
+        .iter()
+        .map(|req| match req {
+            Request::Http(HttpRequest { url, .. }) => url.as_str(),
+            other => panic!("expected a GET, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(urls.len(), 2);
+    assert!(urls[0].starts_with("https://api.example.com/data?ts="));
+    assert!(urls[1].starts_with("https://api.example.com/meta?ts="));
+
+    rt.resolve::<HttpRequest<_>, _>(1, ok("meta"));
+    rt.resolve::<HttpRequest<_>, _>(0, ok("data"));
+    assert!(rt.step());
+    assert_eq!(rt.model().responses, 1);
+    assert_eq!(rt.model().last_response.as_deref(), Some("meta"));
+    assert!(!rt.is_shut_down());
+    assert!(rt.step());
+    assert!(rt.is_shut_down());
+    assert!(rt.pending_requests().is_empty());
+}
+
+#[test]
+fn test_runtime_ignores_messages_after_shutdown() {
+    let mut rt = TestRuntime::new(app::init, app::update);
+    rt.send(Msg::GotApiResponse("a".into()));
+    rt.send(Msg::GotApiResponse("b".into()));
+    rt.send(Msg::GotApiResponse("c".into()));
+    while rt.step() {}
+    assert!(rt.is_shut_down());
+    assert_eq!(rt.model().responses, 2);
+}
+
+// `resolve` runs the request's own callback, so an app that wired the wrong
+// one is caught; `complete_with_msg` skips it.
+#[test]
+fn test_runtime_resolves_through_the_request_callback() {
+    let mut rt = TestRuntime::new(app::init, app::update);
+    rt.complete_with_msg(0, Msg::GotTime("noon".into()));
+    assert!(rt.step());
+    assert_eq!(rt.model().req_time.as_deref(), Some("noon"));
+
+    rt.resolve::<HttpRequest<_>, _>(0, Err(mock_http_manager::HttpError::Timeout));
+    assert!(rt.step());
+    assert_eq!(rt.model().responses, 1);
+    assert_eq!(
+        rt.model().last_response.as_deref(),
+        Some("error: request timed out")
+    );
+}
+
+#[test]
+#[should_panic(expected = "pending request 0 is not a")]
+fn test_runtime_rejects_resolving_with_another_managers_type() {
+    let mut rt = TestRuntime::new(app::init, app::update);
+    rt.resolve::<HttpRequest<_>, _>(0, ok("fine"));
+}
@@ -1,18 +1,20 @@
 pub mod requests;
 pub use requests::{clear, notify_after, notify_at, time_now};
-use saucer_core::Router;
-pub use saucer_time_manager::TimeRequest;
+use saucer_core::{EffectManager, Router};
+pub use saucer_time_manager::{Posix, TimeRequest, Zone};
 
 // === Effect Manager ===
 
 pub struct TimeManager;
 
-impl TimeManager {
-    pub fn init() -> () {
-        ()
-    }
+impl EffectManager for TimeManager {
+    type State<Msg> = ();
+    type Request<Msg> = TimeRequest<Msg>;
+    type SelfMsg = ();
+
+    fn init<Msg: Send + 'static>() {}
 
-    pub fn on_effects<Msg: Send + 'static>(
+    fn on_effects<Msg: Send + 'static>(
         &self,
         router: &Router<Msg, ()>,
         state: (),
@@ -21,7 +23,10 @@ impl TimeManager {
         for req in effects {
             match req {
                 TimeRequest::Now { returns } => {
-                    router.send_to_app(returns(1234567890.0));
+                    router.send_to_app(returns(Posix::from_millis(1234567890000)));
+                }
+                TimeRequest::Here { returns } => {
+                    router.send_to_app(returns(Zone::utc()));
                 }
                 TimeRequest::NotifyAfter { returns, .. } => {
                     router.send_to_app(returns());
@@ -29,6 +34,10 @@ impl TimeManager {
                 TimeRequest::NotifyAt { returns, .. } => {
                     router.send_to_app(returns());
                 }
+                TimeRequest::Every { returns, .. } => {
+                    // Deterministic mock: a single tick.
+                    router.send_to_app(returns(Posix::from_millis(1234567890000)));
+                }
                 TimeRequest::Clear { returns, .. } => {
                     router.send_to_app(returns());
                 }
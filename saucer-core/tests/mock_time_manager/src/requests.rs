@@ -1,4 +1,4 @@
-use saucer_time_manager::TimeRequest;
+use saucer_time_manager::{Posix, TimeRequest};
 use std::sync::Arc;
 
 pub fn notify_after<Msg: 'static>(
@@ -15,18 +15,18 @@ pub fn notify_after<Msg: 'static>(
 
 pub fn notify_at<Msg: 'static>(
     id: u64,
-    instant_secs: u64,
+    at: std::time::SystemTime,
     returns: impl Fn() -> Msg + Send + Sync + 'static,
 ) -> TimeRequest<Msg> {
     TimeRequest::NotifyAt {
         id,
-        instant_secs,
+        at,
         returns: Arc::new(returns),
     }
 }
 
 pub fn time_now<Msg: 'static>(
-    returns: impl Fn(f64) -> Msg + Send + Sync + 'static,
+    returns: impl Fn(Posix) -> Msg + Send + Sync + 'static,
 ) -> TimeRequest<Msg> {
     TimeRequest::Now {
         returns: Arc::new(returns),
@@ -8,6 +8,8 @@ pub mod runtime {
 use runtime::mock_app::app;
 #[allow(unused_imports)]
 use runtime::mock_wrapper_app::wrapper;
+#[allow(unused_imports)]
+use runtime::mock_wrapper_app::widgets;
 
 type WrapperModel = runtime::mock_wrapper_app::wrapper::Model<runtime::mock_app::app::Model>;
 type WrapperMsg = runtime::mock_wrapper_app::wrapper::Msg<runtime::mock_app::app::Msg>;
@@ -17,6 +19,7 @@ type WrapperMsg = runtime::mock_wrapper_app::wrapper::Msg<runtime::mock_app::app
 pub fn build_runtime() -> runtime::sync::Runtime<
     impl FnOnce() -> (WrapperModel, runtime::Cmd<WrapperMsg>),
     impl Fn(WrapperModel, WrapperMsg) -> (WrapperModel, runtime::Cmd<WrapperMsg>),
+    impl Fn(&WrapperModel) -> runtime::Sub<WrapperMsg>,
     impl Fn(&WrapperModel) -> (),
     impl FnMut(&(), &saucer_core::SendToManager<saucer_core::CoreManager, runtime::SelfMsg>),
     WrapperModel,
@@ -35,6 +38,7 @@ pub fn build_runtime() -> runtime::sync::Runtime<
     Runtime::new(
         move || (wrapped.init)(),
         move |m, msg| (wrapped.update)(m, msg),
+        saucer_core::no_subscriptions(),
         move |m| (wrapped.view)(m),
         saucer_core::no_op_reconciler(),
         saucer_core::tracing_observer(),
@@ -0,0 +1,2 @@
+//! Re-exports the widget template to hosts of this crate.
+pub use mock_widget::widget;
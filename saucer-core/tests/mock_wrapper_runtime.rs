@@ -18,3 +18,16 @@ fn mock_wrapper_runtime_runs() {
             .expect("runtime should complete");
     });
 }
+
+// A `pub use` of another crate's template keeps its visibility when it is
+// rewritten, so hosts reach the widget through the re-export.
+#[test]
+fn mock_wrapper_reexports_the_widget_template() {
+    use mock_wrapper_app::runtime::mock_wrapper_app::{widgets, wrapper};
+
+    let msg: wrapper::Msg<()> = wrapper::Msg::Widget(widgets::widget::Msg::Triggered);
+    assert!(matches!(
+        msg,
+        wrapper::Msg::Widget(widgets::widget::Msg::Triggered)
+    ));
+}
@@ -0,0 +1,58 @@
+//! Elm-style response decoders.
+//!
+//! Each `expect_*` wraps an app callback over a typed result and yields the
+//! raw `returns` callback that request helpers take:
+//!
+//! ```ignore
+//! get(url, expect_json(Msg::GotItems))
+//! ```
+
+use serde::de::DeserializeOwned;
+
+use crate::{HttpError, HttpResponse};
+
+/// Passes transport errors through and turns non-2xx statuses into `BadStatus`.
+fn good_status(result: Result<HttpResponse, HttpError>) -> Result<HttpResponse, HttpError> {
+    let resp = result?;
+    if (200..300).contains(&resp.status) {
+        Ok(resp)
+    } else {
+        Err(HttpError::BadStatus(resp.status))
+    }
+}
+
+/// Decode the body as UTF-8 text.
+pub fn expect_string<Msg>(
+    to_msg: impl Fn(Result<String, HttpError>) -> Msg + Send + Sync + 'static,
+) -> impl Fn(Result<HttpResponse, HttpError>) -> Msg + Send + Sync + 'static {
+    move |result| {
+        to_msg(good_status(result).and_then(|resp| {
+            String::from_utf8(resp.body).map_err(|e| HttpError::BadBody(e.to_string()))
+        }))
+    }
+}
+
+/// Decode the body as JSON into `T`.
+pub fn expect_json<T: DeserializeOwned, Msg>(
+    to_msg: impl Fn(Result<T, HttpError>) -> Msg + Send + Sync + 'static,
+) -> impl Fn(Result<HttpResponse, HttpError>) -> Msg + Send + Sync + 'static {
+    move |result| {
+        to_msg(good_status(result).and_then(|resp| {
+            serde_json::from_slice(&resp.body).map_err(|e| HttpError::BadBody(e.to_string()))
+        }))
+    }
+}
+
+/// Hand back the raw body bytes.
+pub fn expect_bytes<Msg>(
+    to_msg: impl Fn(Result<Vec<u8>, HttpError>) -> Msg + Send + Sync + 'static,
+) -> impl Fn(Result<HttpResponse, HttpError>) -> Msg + Send + Sync + 'static {
+    move |result| to_msg(good_status(result).map(|resp| resp.body))
+}
+
+/// Ignore the body; only success or failure matters.
+pub fn expect_whatever<Msg>(
+    to_msg: impl Fn(Result<(), HttpError>) -> Msg + Send + Sync + 'static,
+) -> impl Fn(Result<HttpResponse, HttpError>) -> Msg + Send + Sync + 'static {
+    move |result| to_msg(good_status(result).map(|_| ()))
+}
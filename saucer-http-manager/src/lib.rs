@@ -1,16 +1,65 @@
-//! HTTP request types for saucer.
+//! saucer-http-manager: HTTP effect manager for saucer-core.
 //!
-//! This is a contrived stand-in to exercise saucer-core codegen/runtime.
-//! The API will be replaced by a real saucer-http-manager that mirrors Elm-style
-//! `Http.get { url, expect }` ergonomics; do not treat this surface as stable.
+//! Fictional imports expect helpers under `saucer_http_manager::command::*`.
+//! The real helpers live in `requests.rs`; `command.rs` is an empty breadcrumb.
+//!
+//! Requests run on tokio via `reqwest`. Raw `returns` callbacks see every
+//! response, whatever its status, as `Ok(HttpResponse)`; transport failures
+//! are delivered as `Err(HttpError)`. The `expect_*` combinators mirror Elm's
+//! `Http.expect*`: they turn non-2xx statuses into `BadStatus` and decode the
+//! body into a typed result.
+//!
+//! Requests may carry a `tracker` id. `cancel(tracker)` aborts the request
+//! with that id, and a new request reusing a tracker supersedes the old one.
+//! A tracked request's result goes back through the manager as an
+//! [`HttpSelfMsg`] carrying the tracker and its generation, and is dropped
+//! there if that generation was superseded or cancelled in the meantime, so
+//! a request that had already finished is discarded too.
+//!
+//! The `track(tracker, ..)` subscription reports upload and download progress
+//! for the request carrying that tracker while it streams.
 
+mod expect;
+pub mod requests;
+pub use expect::{expect_bytes, expect_json, expect_string, expect_whatever};
+pub use requests::{cancel, delete, get, head, options, patch, post, put, request, track};
+// Re-exported so generated helpers can name the parameter types used in `requests.rs`.
+pub use std::time::Duration;
+
+use saucer_core::{EffectManager, Router, SubscriptionManager};
 use saucer_core_macros::Request;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
-#[derive(Clone, Debug)]
+/// Upload chunk size used when a tracked request streams its body.
+const SEND_CHUNK: usize = 16 * 1024;
+
+type ProgressTagger<Msg> = Arc<dyn Fn(Progress) -> Msg + Send + Sync>;
+type ProgressReporter = Arc<dyn Fn(Progress) + Send + Sync>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum HttpMethod {
     Get,
     Post,
+    Put,
+    Patch,
+    Delete,
+    Head,
+    Options,
+}
+
+impl From<HttpMethod> for reqwest::Method {
+    fn from(method: HttpMethod) -> Self {
+        match method {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Post => reqwest::Method::POST,
+            HttpMethod::Put => reqwest::Method::PUT,
+            HttpMethod::Patch => reqwest::Method::PATCH,
+            HttpMethod::Delete => reqwest::Method::DELETE,
+            HttpMethod::Head => reqwest::Method::HEAD,
+            HttpMethod::Options => reqwest::Method::OPTIONS,
+        }
+    }
 }
 
 #[derive(Clone, Request)]
@@ -19,9 +68,48 @@ pub struct HttpRequest<Msg> {
     pub url: String,
     pub headers: Vec<(String, String)>,
     pub body: Option<Vec<u8>>, // None for GET
+    pub timeout: Option<Duration>,
+    pub tracker: Option<String>,
     pub returns: Arc<dyn Fn(Result<HttpResponse, HttpError>) -> Msg + Send + Sync>,
 }
 
+/// Transfer progress for a tracked request, following Elm's `Http.Progress`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Progress {
+    Sending { sent: u64, size: u64 },
+    Receiving { received: u64, size: Option<u64> },
+}
+
+/// Subscriptions offered by the HTTP manager.
+#[derive(Clone, Request)]
+pub enum HttpSub<Msg> {
+    Track {
+        tracker: String,
+        returns: ProgressTagger<Msg>,
+    },
+}
+
+impl<Msg: 'static> HttpSub<Msg> {
+    pub fn map<Msg2>(
+        self,
+        f: impl Fn(Msg) -> Msg2 + Send + Sync + Clone + 'static,
+    ) -> HttpSub<Msg2> {
+        match self {
+            HttpSub::Track { tracker, returns } => HttpSub::Track {
+                tracker,
+                returns: Arc::new(move |p| f(returns(p))),
+            },
+        }
+    }
+}
+
+/// Everything the HTTP manager can be asked to do.
+#[derive(Clone, Request)]
+pub enum HttpEffect<Msg> {
+    Send(HttpRequest<Msg>),
+    Cancel { tracker: String },
+}
+
 #[derive(Clone, Debug)]
 pub struct HttpResponse {
     pub status: u16,
@@ -29,11 +117,35 @@ pub struct HttpResponse {
     pub body: Vec<u8>,
 }
 
-#[derive(Clone, Debug)]
-pub struct HttpError {
-    pub message: String,
+/// Why a request failed, following Elm's `Http.Error`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HttpError {
+    /// The URL could not be parsed.
+    BadUrl(String),
+    /// The request did not complete in time.
+    Timeout,
+    /// The connection failed (DNS, refused, reset, TLS).
+    NetworkError,
+    /// The server answered with a non-2xx status.
+    BadStatus(u16),
+    /// The body could not be decoded into the expected type.
+    BadBody(String),
 }
 
+impl std::fmt::Display for HttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpError::BadUrl(url) => write!(f, "bad url: {}", url),
+            HttpError::Timeout => write!(f, "request timed out"),
+            HttpError::NetworkError => write!(f, "network error"),
+            HttpError::BadStatus(status) => write!(f, "bad status: {}", status),
+            HttpError::BadBody(reason) => write!(f, "bad body: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
 impl<Msg: 'static> HttpRequest<Msg> {
     pub fn map<Msg2>(
         self,
@@ -46,9 +158,304 @@ impl<Msg: 'static> HttpRequest<Msg> {
             url: self.url,
             headers: self.headers,
             body: self.body,
+            timeout: self.timeout,
+            tracker: self.tracker,
             returns: Arc::new(move |r| f(returns(r))),
         }
     }
 }
-pub mod requests;
-pub use requests::{get, post};
+
+impl<Msg: 'static> HttpEffect<Msg> {
+    pub fn map<Msg2>(
+        self,
+        f: impl Fn(Msg) -> Msg2 + Send + Sync + Clone + 'static,
+    ) -> HttpEffect<Msg2> {
+        match self {
+            HttpEffect::Send(req) => HttpEffect::Send(req.map(f)),
+            HttpEffect::Cancel { tracker } => HttpEffect::Cancel { tracker },
+        }
+    }
+}
+
+impl From<reqwest::Error> for HttpError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            HttpError::Timeout
+        } else {
+            HttpError::NetworkError
+        }
+    }
+}
+
+/// Sent by a tracked request's task once its result is ready.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HttpSelfMsg {
+    Completed { tracker: String, generation: u64 },
+}
+
+/// The current request for a tracker, until its result is delivered.
+struct Tracked {
+    generation: u64,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+/// Shared connection pool plus tracked requests, so they can be cancelled
+/// by tracker id. Finished tracked requests park their message in
+/// `completed` until `on_self_msg` confirms the generation is still current.
+/// `progress` holds the current `track` subscriptions and is shared with
+/// request tasks.
+pub struct HttpState<Msg> {
+    client: reqwest::Client,
+    next_generation: u64,
+    in_flight: HashMap<String, Tracked>,
+    completed: Arc<Mutex<HashMap<(String, u64), Msg>>>,
+    progress: Arc<Mutex<HashMap<String, Vec<ProgressTagger<Msg>>>>>,
+}
+
+impl<Msg> Default for HttpState<Msg> {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            next_generation: 0,
+            in_flight: HashMap::new(),
+            completed: Arc::new(Mutex::new(HashMap::new())),
+            progress: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<Msg> HttpState<Msg> {
+    /// Forget the current request for `tracker`, aborting it if it is still
+    /// running and discarding its result if it already finished.
+    fn drop_tracked(&mut self, tracker: &str) {
+        if let Some(stale) = self.in_flight.remove(tracker) {
+            stale.handle.abort();
+            self.completed
+                .lock()
+                .expect("completed requests mutex poisoned")
+                .remove(&(tracker.to_string(), stale.generation));
+        }
+    }
+}
+
+/// HTTP effect manager backed by `reqwest`.
+pub struct HttpManager;
+
+impl EffectManager for HttpManager {
+    type State<Msg> = HttpState<Msg>;
+    type Request<Msg> = HttpEffect<Msg>;
+    type SelfMsg = HttpSelfMsg;
+
+    fn init<Msg: Send + 'static>() -> HttpState<Msg> {
+        HttpState::default()
+    }
+
+    fn on_effects<Msg: Send + 'static>(
+        &self,
+        router: &Router<Msg, HttpSelfMsg>,
+        mut state: HttpState<Msg>,
+        effects: Vec<HttpEffect<Msg>>,
+    ) -> HttpState<Msg> {
+        for effect in effects {
+            match effect {
+                HttpEffect::Send(req) => {
+                    let HttpRequest {
+                        method,
+                        url,
+                        headers,
+                        body,
+                        timeout,
+                        tracker,
+                        returns,
+                    } = req;
+                    let client = state.client.clone();
+                    let report = tracker.clone().map(|tracker| {
+                        progress_reporter(tracker, Arc::clone(&state.progress), router)
+                    });
+                    let Some(tracker) = tracker else {
+                        let app_sender = router.app_sender();
+                        tokio::spawn(async move {
+                            let result =
+                                send(&client, method, &url, headers, body, timeout, report).await;
+                            let _ = app_sender.send(returns(result));
+                        });
+                        continue;
+                    };
+
+                    state.drop_tracked(&tracker);
+                    let generation = state.next_generation;
+                    state.next_generation += 1;
+                    let completed = Arc::clone(&state.completed);
+                    let self_sender = router.self_sender();
+                    let done = HttpSelfMsg::Completed {
+                        tracker: tracker.clone(),
+                        generation,
+                    };
+                    let handle = tokio::spawn(async move {
+                        let result =
+                            send(&client, method, &url, headers, body, timeout, report).await;
+                        let HttpSelfMsg::Completed { tracker, .. } = &done;
+                        completed
+                            .lock()
+                            .expect("completed requests mutex poisoned")
+                            .insert((tracker.clone(), generation), returns(result));
+                        let _ = self_sender.send(done);
+                    });
+                    state
+                        .in_flight
+                        .insert(tracker, Tracked { generation, handle });
+                }
+                HttpEffect::Cancel { tracker } => state.drop_tracked(&tracker),
+            }
+        }
+        state
+    }
+
+    /// Delivers a tracked request's result unless its generation was
+    /// superseded or cancelled after it finished.
+    fn on_self_msg<Msg: Send + 'static>(
+        &self,
+        router: &Router<Msg, HttpSelfMsg>,
+        mut state: HttpState<Msg>,
+        msg: HttpSelfMsg,
+    ) -> HttpState<Msg> {
+        let HttpSelfMsg::Completed {
+            tracker,
+            generation,
+        } = msg;
+        let current = state
+            .in_flight
+            .get(&tracker)
+            .is_some_and(|tracked| tracked.generation == generation);
+        let result = state
+            .completed
+            .lock()
+            .expect("completed requests mutex poisoned")
+            .remove(&(tracker.clone(), generation));
+        if current {
+            state.in_flight.remove(&tracker);
+            if let Some(result) = result {
+                router.send_to_app(result);
+            }
+        }
+        state
+    }
+}
+
+impl SubscriptionManager for HttpManager {
+    type Subscription<Msg> = HttpSub<Msg>;
+
+    /// Receives the app's complete set of HTTP subscriptions after every
+    /// update. In-flight requests look trackers up on each progress step, so
+    /// replacing the set is all that is needed.
+    fn on_subscriptions<Msg: Send + 'static>(
+        &self,
+        _router: &Router<Msg, HttpSelfMsg>,
+        state: HttpState<Msg>,
+        subs: Vec<HttpSub<Msg>>,
+    ) -> HttpState<Msg> {
+        let mut trackers: HashMap<String, Vec<ProgressTagger<Msg>>> = HashMap::new();
+        for sub in subs {
+            match sub {
+                HttpSub::Track { tracker, returns } => {
+                    trackers.entry(tracker).or_default().push(returns);
+                }
+            }
+        }
+        *state
+            .progress
+            .lock()
+            .expect("progress subscriptions mutex poisoned") = trackers;
+        state
+    }
+}
+
+/// Builds the callback a request task uses to publish progress for `tracker`
+/// to whichever `track` subscriptions are current at that moment.
+fn progress_reporter<Msg: Send + 'static>(
+    tracker: String,
+    progress: Arc<Mutex<HashMap<String, Vec<ProgressTagger<Msg>>>>>,
+    router: &Router<Msg, HttpSelfMsg>,
+) -> ProgressReporter {
+    let app_sender = router.app_sender();
+    Arc::new(move |step| {
+        let subscribed = progress
+            .lock()
+            .expect("progress subscriptions mutex poisoned");
+        for tagger in subscribed.get(&tracker).into_iter().flatten() {
+            let _ = app_sender.send(tagger(step.clone()));
+        }
+    })
+}
+
+async fn send(
+    client: &reqwest::Client,
+    method: HttpMethod,
+    url: &str,
+    headers: Vec<(String, String)>,
+    body: Option<Vec<u8>>,
+    timeout: Option<Duration>,
+    report: Option<ProgressReporter>,
+) -> Result<HttpResponse, HttpError> {
+    let url = reqwest::Url::parse(url).map_err(|_| HttpError::BadUrl(url.to_string()))?;
+    let mut builder = client.request(method.into(), url);
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(timeout);
+    }
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+    match (body, &report) {
+        (Some(body), Some(report)) => {
+            // Stream the body in chunks so each one can be reported as sent.
+            let size = body.len() as u64;
+            let body = bytes::Bytes::from(body);
+            let chunks: Vec<bytes::Bytes> = (0..body.len())
+                .step_by(SEND_CHUNK)
+                .map(|start| body.slice(start..(start + SEND_CHUNK).min(body.len())))
+                .collect();
+            let report = Arc::clone(report);
+            let mut sent = 0u64;
+            let stream = futures_util::stream::iter(chunks.into_iter().map(move |chunk| {
+                sent += chunk.len() as u64;
+                report(Progress::Sending { sent, size });
+                Ok::<_, std::io::Error>(chunk)
+            }));
+            builder = builder
+                .header(reqwest::header::CONTENT_LENGTH, size)
+                .body(reqwest::Body::wrap_stream(stream));
+        }
+        (Some(body), None) => builder = builder.body(body),
+        (None, _) => {}
+    }
+
+    let mut resp = builder.send().await?;
+    let status = resp.status().as_u16();
+    let headers = resp
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.as_str().to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect();
+    let size = resp.content_length();
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        body.extend_from_slice(&chunk);
+        if let Some(report) = &report {
+            report(Progress::Receiving {
+                received: body.len() as u64,
+                size,
+            });
+        }
+    }
+
+    Ok(HttpResponse {
+        status,
+        headers,
+        body,
+    })
+}
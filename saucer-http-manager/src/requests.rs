@@ -1,30 +1,137 @@
 use std::sync::Arc;
+use std::time::Duration;
 
-use crate::{HttpError, HttpMethod, HttpRequest, HttpResponse};
+use crate::{HttpEffect, HttpError, HttpMethod, HttpRequest, HttpResponse, HttpSub, Progress};
 
-pub fn get<Msg>(
+/// Fully specified request, mirroring Elm's `Http.request`. Pass an
+/// `expect_*` combinator (or a raw callback) as `returns`, and a `tracker`
+/// to make the request cancellable.
+pub fn request<Msg>(
+    method: HttpMethod,
     url: impl Into<String>,
+    headers: Vec<(String, String)>,
+    body: Option<Vec<u8>>,
+    timeout: Option<Duration>,
+    tracker: Option<String>,
     returns: impl Fn(Result<HttpResponse, HttpError>) -> Msg + Send + Sync + 'static,
-) -> HttpRequest<Msg> {
-    HttpRequest {
-        method: HttpMethod::Get,
+) -> HttpEffect<Msg> {
+    HttpEffect::Send(HttpRequest {
+        method,
         url: url.into(),
-        headers: Vec::new(),
-        body: None,
+        headers,
+        body,
+        timeout,
+        tracker,
         returns: Arc::new(returns),
+    })
+}
+
+/// Abort the in-flight request started with `tracker`; it delivers nothing.
+pub fn cancel<Msg>(tracker: impl Into<String>) -> HttpEffect<Msg> {
+    HttpEffect::Cancel {
+        tracker: tracker.into(),
     }
 }
 
+pub fn get<Msg>(
+    url: impl Into<String>,
+    returns: impl Fn(Result<HttpResponse, HttpError>) -> Msg + Send + Sync + 'static,
+) -> HttpEffect<Msg> {
+    request(HttpMethod::Get, url, Vec::new(), None, None, None, returns)
+}
+
 pub fn post<Msg>(
     url: impl Into<String>,
     body: impl Into<Vec<u8>>,
     returns: impl Fn(Result<HttpResponse, HttpError>) -> Msg + Send + Sync + 'static,
-) -> HttpRequest<Msg> {
-    HttpRequest {
-        method: HttpMethod::Post,
-        url: url.into(),
-        headers: Vec::new(),
-        body: Some(body.into()),
+) -> HttpEffect<Msg> {
+    request(
+        HttpMethod::Post,
+        url,
+        Vec::new(),
+        Some(body.into()),
+        None,
+        None,
+        returns,
+    )
+}
+
+pub fn put<Msg>(
+    url: impl Into<String>,
+    body: impl Into<Vec<u8>>,
+    returns: impl Fn(Result<HttpResponse, HttpError>) -> Msg + Send + Sync + 'static,
+) -> HttpEffect<Msg> {
+    request(
+        HttpMethod::Put,
+        url,
+        Vec::new(),
+        Some(body.into()),
+        None,
+        None,
+        returns,
+    )
+}
+
+pub fn patch<Msg>(
+    url: impl Into<String>,
+    body: impl Into<Vec<u8>>,
+    returns: impl Fn(Result<HttpResponse, HttpError>) -> Msg + Send + Sync + 'static,
+) -> HttpEffect<Msg> {
+    request(
+        HttpMethod::Patch,
+        url,
+        Vec::new(),
+        Some(body.into()),
+        None,
+        None,
+        returns,
+    )
+}
+
+pub fn delete<Msg>(
+    url: impl Into<String>,
+    returns: impl Fn(Result<HttpResponse, HttpError>) -> Msg + Send + Sync + 'static,
+) -> HttpEffect<Msg> {
+    request(
+        HttpMethod::Delete,
+        url,
+        Vec::new(),
+        None,
+        None,
+        None,
+        returns,
+    )
+}
+
+pub fn head<Msg>(
+    url: impl Into<String>,
+    returns: impl Fn(Result<HttpResponse, HttpError>) -> Msg + Send + Sync + 'static,
+) -> HttpEffect<Msg> {
+    request(HttpMethod::Head, url, Vec::new(), None, None, None, returns)
+}
+
+pub fn options<Msg>(
+    url: impl Into<String>,
+    returns: impl Fn(Result<HttpResponse, HttpError>) -> Msg + Send + Sync + 'static,
+) -> HttpEffect<Msg> {
+    request(
+        HttpMethod::Options,
+        url,
+        Vec::new(),
+        None,
+        None,
+        None,
+        returns,
+    )
+}
+
+/// Subscribe to upload/download progress of the request sent with `tracker`.
+pub fn track<Msg>(
+    tracker: impl Into<String>,
+    returns: impl Fn(Progress) -> Msg + Send + Sync + 'static,
+) -> HttpSub<Msg> {
+    HttpSub::Track {
+        tracker: tracker.into(),
         returns: Arc::new(returns),
     }
 }
@@ -0,0 +1,67 @@
+use saucer_http_manager::{
+    expect_bytes, expect_json, expect_string, expect_whatever, HttpError, HttpResponse,
+};
+use serde::Deserialize;
+
+fn response(status: u16, body: &[u8]) -> Result<HttpResponse, HttpError> {
+    Ok(HttpResponse {
+        status,
+        headers: Vec::new(),
+        body: body.to_vec(),
+    })
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Item {
+    id: u32,
+    name: String,
+}
+
+#[test]
+fn expect_json_decodes_body() {
+    let returns = expect_json(|r: Result<Item, HttpError>| r);
+    assert_eq!(
+        returns(response(200, br#"{"id":7,"name":"saucer"}"#)),
+        Ok(Item {
+            id: 7,
+            name: "saucer".to_string()
+        })
+    );
+}
+
+#[test]
+fn expect_json_reports_bad_body() {
+    let returns = expect_json(|r: Result<Item, HttpError>| r);
+    assert!(matches!(
+        returns(response(200, b"{\"id\":")),
+        Err(HttpError::BadBody(_))
+    ));
+}
+
+#[test]
+fn expect_string_rejects_non_2xx_and_invalid_utf8() {
+    let returns = expect_string(|r| r);
+    assert_eq!(returns(response(200, b"hi")), Ok("hi".to_string()));
+    assert_eq!(
+        returns(response(503, b"down")),
+        Err(HttpError::BadStatus(503))
+    );
+    assert!(matches!(
+        returns(response(200, &[0xff, 0xfe])),
+        Err(HttpError::BadBody(_))
+    ));
+}
+
+#[test]
+fn expect_bytes_and_whatever_pass_transport_errors_through() {
+    let bytes = expect_bytes(|r| r);
+    assert_eq!(bytes(response(204, b"")), Ok(Vec::new()));
+    assert_eq!(bytes(Err(HttpError::Timeout)), Err(HttpError::Timeout));
+
+    let whatever = expect_whatever(|r| r);
+    assert_eq!(whatever(response(201, b"ignored")), Ok(()));
+    assert_eq!(
+        whatever(Err(HttpError::NetworkError)),
+        Err(HttpError::NetworkError)
+    );
+}
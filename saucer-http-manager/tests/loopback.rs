@@ -0,0 +1,676 @@
+use std::time::Duration;
+
+use saucer_core::{EffectManager, Router, RouterChannels, SubscriptionManager};
+use saucer_http_manager::{
+    cancel, delete, get, request, track, HttpEffect, HttpError, HttpManager, HttpMethod,
+    HttpResponse, HttpSelfMsg, HttpState, Progress,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Builder;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::oneshot;
+
+type HttpResult = Result<HttpResponse, HttpError>;
+
+const OK_EMPTY: &str = "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const STALE: &str = "HTTP/1.1 410 Gone\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+const NOT_FOUND: &str =
+    "HTTP/1.1 404 Not Found\r\nX-Test: yes\r\nContent-Length: 7\r\nConnection: close\r\n\r\nmissing";
+
+/// Accepts a single connection, captures the raw request, and answers with
+/// `response`. Resolves to the captured request text.
+async fn serve_once(listener: TcpListener, response: &'static str) -> String {
+    let (mut socket, _) = listener.accept().await.expect("accept");
+    let raw = read_request(&mut socket).await;
+    socket
+        .write_all(response.as_bytes())
+        .await
+        .expect("write response");
+    socket.shutdown().await.ok();
+    String::from_utf8_lossy(&raw).into_owned()
+}
+
+/// Reads one request from `socket`, up to the end of its body.
+async fn read_request(socket: &mut TcpStream) -> Vec<u8> {
+    let mut raw = Vec::new();
+    let mut buf = [0u8; 1024];
+    loop {
+        let n = socket.read(&mut buf).await.expect("read request");
+        if n == 0 {
+            break;
+        }
+        raw.extend_from_slice(&buf[..n]);
+        if request_complete(&raw) {
+            break;
+        }
+    }
+    raw
+}
+
+fn request_complete(raw: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(raw);
+    let Some(head_end) = text.find("\r\n\r\n") else {
+        return false;
+    };
+    let content_length = text[..head_end]
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    raw.len() >= head_end + 4 + content_length
+}
+
+async fn recv(app_rx: &mut UnboundedReceiver<HttpResult>) -> HttpResult {
+    tokio::time::timeout(Duration::from_secs(5), app_rx.recv())
+        .await
+        .expect("request should complete")
+        .expect("manager should deliver a message")
+}
+
+/// Waits for a tracked request to finish and hands its completion back to
+/// the manager, as the runtime would.
+async fn settle<Msg: Send + 'static>(
+    manager: &HttpManager,
+    router: &Router<Msg, HttpSelfMsg>,
+    state: HttpState<Msg>,
+    self_rx: &mut UnboundedReceiver<HttpSelfMsg>,
+) -> HttpState<Msg> {
+    let msg = next_self_msg(self_rx).await;
+    manager.on_self_msg(router, state, msg)
+}
+
+/// Hands every completion reported so far back to the manager.
+fn drain<Msg: Send + 'static>(
+    manager: &HttpManager,
+    router: &Router<Msg, HttpSelfMsg>,
+    mut state: HttpState<Msg>,
+    self_rx: &mut UnboundedReceiver<HttpSelfMsg>,
+) -> HttpState<Msg> {
+    while let Ok(msg) = self_rx.try_recv() {
+        state = manager.on_self_msg(router, state, msg);
+    }
+    state
+}
+
+async fn next_self_msg(self_rx: &mut UnboundedReceiver<HttpSelfMsg>) -> HttpSelfMsg {
+    tokio::time::timeout(Duration::from_secs(5), self_rx.recv())
+        .await
+        .expect("tracked request should complete")
+        .expect("manager should report completion")
+}
+
+#[test]
+fn get_delivers_status_headers_and_body() {
+    let rt = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let server = tokio::spawn(serve_once(listener, NOT_FOUND));
+
+        let RouterChannels {
+            router, mut app_rx, ..
+        } = RouterChannels::<HttpResult, HttpSelfMsg>::new();
+        let manager = HttpManager;
+        let state = HttpManager::init();
+        let state = manager.on_effects(
+            &router,
+            state,
+            vec![get(format!("http://{addr}/items"), |r| r)],
+        );
+
+        let resp = recv(&mut app_rx)
+            .await
+            .expect("non-2xx is still a response");
+        assert_eq!(resp.status, 404);
+        assert_eq!(resp.body, b"missing");
+        assert!(resp
+            .headers
+            .iter()
+            .any(|(name, value)| name == "x-test" && value == "yes"));
+
+        let request = server.await.expect("server task");
+        assert!(request.starts_with("GET /items HTTP/1.1\r\n"));
+
+        drop(state);
+    });
+}
+
+#[test]
+fn request_sends_body_and_headers() {
+    let rt = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let server = tokio::spawn(serve_once(
+            listener,
+            "HTTP/1.1 201 Created\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ));
+
+        let RouterChannels {
+            router, mut app_rx, ..
+        } = RouterChannels::<HttpResult, HttpSelfMsg>::new();
+        let manager = HttpManager;
+        let state = HttpManager::init();
+        let req = request(
+            HttpMethod::Post,
+            format!("http://{addr}/items"),
+            vec![("X-Trace".to_string(), "abc123".to_string())],
+            Some(b"hello".to_vec()),
+            None,
+            None,
+            |r| r,
+        );
+        let state = manager.on_effects(&router, state, vec![req]);
+
+        let resp = recv(&mut app_rx).await.expect("response");
+        assert_eq!(resp.status, 201);
+
+        let request = server.await.expect("server task");
+        assert!(request.starts_with("POST /items HTTP/1.1\r\n"));
+        assert!(request.to_ascii_lowercase().contains("x-trace: abc123\r\n"));
+        assert!(request.ends_with("\r\n\r\nhello"));
+
+        drop(state);
+    });
+}
+
+#[test]
+fn connection_failure_maps_to_http_error() {
+    let rt = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        // Reserve a port, then close it so the connect is refused.
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        drop(listener);
+
+        let RouterChannels {
+            router, mut app_rx, ..
+        } = RouterChannels::<HttpResult, HttpSelfMsg>::new();
+        let manager = HttpManager;
+        let state = HttpManager::init();
+        let state = manager.on_effects(&router, state, vec![get(format!("http://{addr}/"), |r| r)]);
+
+        let err = recv(&mut app_rx).await.expect_err("connect should fail");
+        assert_eq!(err, HttpError::NetworkError);
+
+        drop(state);
+    });
+}
+
+#[test]
+fn unparseable_url_maps_to_bad_url() {
+    let rt = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        let RouterChannels {
+            router, mut app_rx, ..
+        } = RouterChannels::<HttpResult, HttpSelfMsg>::new();
+        let manager = HttpManager;
+        let state = HttpManager::init();
+        let state = manager.on_effects(&router, state, vec![get("not a url", |r| r)]);
+
+        let err = recv(&mut app_rx).await.expect_err("url should be rejected");
+        assert_eq!(err, HttpError::BadUrl("not a url".to_string()));
+
+        drop(state);
+    });
+}
+
+#[test]
+fn request_sends_method_and_headers() {
+    let rt = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let server = tokio::spawn(serve_once(listener, OK_EMPTY));
+
+        let RouterChannels {
+            router, mut app_rx, ..
+        } = RouterChannels::<HttpResult, HttpSelfMsg>::new();
+        let manager = HttpManager;
+        let state = HttpManager::init();
+        let state = manager.on_effects(
+            &router,
+            state,
+            vec![request(
+                HttpMethod::Put,
+                format!("http://{addr}/items/1"),
+                vec![("Authorization".to_string(), "Bearer t0k3n".to_string())],
+                Some(b"{}".to_vec()),
+                Some(Duration::from_secs(5)),
+                None,
+                |r| r,
+            )],
+        );
+
+        let resp = recv(&mut app_rx).await.expect("response");
+        assert_eq!(resp.status, 200);
+
+        let request = server.await.expect("server task");
+        assert!(request.starts_with("PUT /items/1 HTTP/1.1\r\n"));
+        assert!(request
+            .to_ascii_lowercase()
+            .contains("authorization: bearer t0k3n\r\n"));
+
+        drop(state);
+    });
+}
+
+#[test]
+fn delete_uses_delete_method() {
+    let rt = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let server = tokio::spawn(serve_once(
+            listener,
+            "HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n",
+        ));
+
+        let RouterChannels {
+            router, mut app_rx, ..
+        } = RouterChannels::<HttpResult, HttpSelfMsg>::new();
+        let manager = HttpManager;
+        let state = HttpManager::init();
+        let state = manager.on_effects(
+            &router,
+            state,
+            vec![delete(format!("http://{addr}/items/1"), |r| r)],
+        );
+
+        let resp = recv(&mut app_rx).await.expect("response");
+        assert_eq!(resp.status, 204);
+        let request = server.await.expect("server task");
+        assert!(request.starts_with("DELETE /items/1 HTTP/1.1\r\n"));
+
+        drop(state);
+    });
+}
+
+#[test]
+fn elapsed_timeout_maps_to_timeout() {
+    let rt = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        // Accept the connection but never answer.
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.expect("accept");
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            drop(socket);
+        });
+
+        let RouterChannels {
+            router, mut app_rx, ..
+        } = RouterChannels::<HttpResult, HttpSelfMsg>::new();
+        let manager = HttpManager;
+        let state = HttpManager::init();
+        let state = manager.on_effects(
+            &router,
+            state,
+            vec![request(
+                HttpMethod::Get,
+                format!("http://{addr}/slow"),
+                Vec::new(),
+                None,
+                Some(Duration::from_millis(50)),
+                None,
+                |r| r,
+            )],
+        );
+
+        let err = recv(&mut app_rx)
+            .await
+            .expect_err("request should time out");
+        assert_eq!(err, HttpError::Timeout);
+
+        server.abort();
+        drop(state);
+    });
+}
+
+fn tracked_get(url: String, tracker: &str) -> HttpEffect<HttpResult> {
+    request(
+        HttpMethod::Get,
+        url,
+        Vec::new(),
+        None,
+        None,
+        Some(tracker.to_string()),
+        |r| r,
+    )
+}
+
+#[test]
+fn cancel_aborts_tracked_request() {
+    let rt = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        // Hold the response until released, so the cancel lands while the
+        // request is in flight.
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let (received_tx, received_rx) = oneshot::channel();
+        let (release_tx, release_rx) = oneshot::channel::<()>();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.expect("accept");
+            read_request(&mut socket).await;
+            received_tx.send(()).expect("test should await the request");
+            let _ = release_rx.await;
+            let _ = socket.write_all(OK_EMPTY.as_bytes()).await;
+        });
+
+        let RouterChannels {
+            router,
+            mut app_rx,
+            mut self_rx,
+        } = RouterChannels::<HttpResult, HttpSelfMsg>::new();
+        let manager = HttpManager;
+        let state = HttpManager::init();
+        let state = manager.on_effects(
+            &router,
+            state,
+            vec![tracked_get(format!("http://{addr}/search?q=sa"), "search")],
+        );
+        received_rx.await.expect("request should reach the server");
+        let state = manager.on_effects(&router, state, vec![cancel("search")]);
+        release_tx.send(()).expect("server should be waiting");
+        server.await.expect("server");
+
+        // Once the aborted task is gone nothing holds a sender, so the
+        // channels close; a completion must not have been reported first.
+        drop(state);
+        drop(router);
+        let completion = tokio::time::timeout(Duration::from_secs(5), self_rx.recv())
+            .await
+            .expect("aborted request should release its sender");
+        assert!(
+            completion.is_none(),
+            "cancelled request should not report completion: {completion:?}"
+        );
+        assert!(
+            app_rx.recv().await.is_none(),
+            "cancelled request should not deliver"
+        );
+    });
+}
+
+#[test]
+fn reusing_a_tracker_supersedes_the_previous_request() {
+    let rt = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        let slow = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let slow_addr = slow.local_addr().expect("addr");
+        let slow_server = tokio::spawn(async move {
+            let (mut socket, _) = slow.accept().await.expect("accept");
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let _ = socket.write_all(STALE.as_bytes()).await;
+        });
+        let fast = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let fast_addr = fast.local_addr().expect("addr");
+        let fast_server = tokio::spawn(serve_once(fast, OK_EMPTY));
+
+        let RouterChannels {
+            router,
+            mut app_rx,
+            mut self_rx,
+        } = RouterChannels::<HttpResult, HttpSelfMsg>::new();
+        let manager = HttpManager;
+        let state = HttpManager::init();
+        let state = manager.on_effects(
+            &router,
+            state,
+            vec![tracked_get(
+                format!("http://{slow_addr}/search?q=sa"),
+                "search",
+            )],
+        );
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let state = manager.on_effects(
+            &router,
+            state,
+            vec![tracked_get(
+                format!("http://{fast_addr}/search?q=sau"),
+                "search",
+            )],
+        );
+
+        let state = settle(&manager, &router, state, &mut self_rx).await;
+        let resp = recv(&mut app_rx).await.expect("fresh response");
+        assert_eq!(resp.status, 200);
+        fast_server.await.expect("server task");
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let state = drain(&manager, &router, state, &mut self_rx);
+        assert!(
+            app_rx.try_recv().is_err(),
+            "superseded request should not deliver"
+        );
+
+        slow_server.abort();
+        drop(state);
+    });
+}
+
+#[test]
+fn superseding_a_finished_request_drops_its_result() {
+    let rt = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        let first = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let first_addr = first.local_addr().expect("addr");
+        let first_server = tokio::spawn(serve_once(first, STALE));
+        let second = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let second_addr = second.local_addr().expect("addr");
+        let second_server = tokio::spawn(serve_once(second, OK_EMPTY));
+
+        let RouterChannels {
+            router,
+            mut app_rx,
+            mut self_rx,
+        } = RouterChannels::<HttpResult, HttpSelfMsg>::new();
+        let manager = HttpManager;
+        let state = HttpManager::init();
+        let state = manager.on_effects(
+            &router,
+            state,
+            vec![tracked_get(
+                format!("http://{first_addr}/search?q=sa"),
+                "search",
+            )],
+        );
+        // The first request has finished, but the runtime has not handed its
+        // completion back to the manager yet.
+        let finished = next_self_msg(&mut self_rx).await;
+        first_server.await.expect("server task");
+
+        let state = manager.on_effects(
+            &router,
+            state,
+            vec![tracked_get(
+                format!("http://{second_addr}/search?q=sau"),
+                "search",
+            )],
+        );
+        let state = manager.on_self_msg(&router, state, finished);
+        assert!(
+            app_rx.try_recv().is_err(),
+            "superseded request should not deliver"
+        );
+
+        let state = settle(&manager, &router, state, &mut self_rx).await;
+        let resp = recv(&mut app_rx).await.expect("fresh response");
+        assert_eq!(resp.status, 200);
+        second_server.await.expect("server task");
+
+        drop(state);
+    });
+}
+
+#[test]
+fn cancelling_a_finished_request_drops_its_result() {
+    let rt = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let server = tokio::spawn(serve_once(listener, OK_EMPTY));
+
+        let RouterChannels {
+            router,
+            mut app_rx,
+            mut self_rx,
+        } = RouterChannels::<HttpResult, HttpSelfMsg>::new();
+        let manager = HttpManager;
+        let state = HttpManager::init();
+        let state = manager.on_effects(
+            &router,
+            state,
+            vec![tracked_get(format!("http://{addr}/search?q=sa"), "search")],
+        );
+        let finished = next_self_msg(&mut self_rx).await;
+        server.await.expect("server task");
+
+        let state = manager.on_effects(&router, state, vec![cancel("search")]);
+        let state = manager.on_self_msg(&router, state, finished);
+        assert!(
+            app_rx.try_recv().is_err(),
+            "cancelled request should not deliver"
+        );
+
+        drop(state);
+    });
+}
+
+#[derive(Debug)]
+enum Transfer {
+    Progress(Progress),
+    Done(HttpResult),
+}
+
+#[test]
+fn track_reports_sending_and_receiving_progress() {
+    let rt = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        const SIZE: usize = 40 * 1024;
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.expect("accept");
+            let mut raw = Vec::new();
+            let mut buf = [0u8; 8192];
+            while !request_complete(&raw) {
+                let n = socket.read(&mut buf).await.expect("read request");
+                if n == 0 {
+                    break;
+                }
+                raw.extend_from_slice(&buf[..n]);
+            }
+            let head =
+                format!("HTTP/1.1 200 OK\r\nContent-Length: {SIZE}\r\nConnection: close\r\n\r\n");
+            socket.write_all(head.as_bytes()).await.expect("write head");
+            socket.write_all(&[b'x'; SIZE]).await.expect("write body");
+        });
+
+        let RouterChannels {
+            router,
+            mut app_rx,
+            mut self_rx,
+        } = RouterChannels::<Transfer, HttpSelfMsg>::new();
+        let manager = HttpManager;
+        let state = HttpManager::init();
+        let state =
+            manager.on_subscriptions(&router, state, vec![track("upload", Transfer::Progress)]);
+        let mut state = manager.on_effects(
+            &router,
+            state,
+            vec![request(
+                HttpMethod::Post,
+                format!("http://{addr}/upload"),
+                Vec::new(),
+                Some(vec![b'y'; SIZE]),
+                None,
+                Some("upload".to_string()),
+                Transfer::Done,
+            )],
+        );
+
+        let mut steps = Vec::new();
+        let result = loop {
+            let msg = tokio::select! {
+                msg = app_rx.recv() => msg.expect("manager should deliver a message"),
+                Some(done) = self_rx.recv() => {
+                    state = manager.on_self_msg(&router, state, done);
+                    continue;
+                }
+                _ = tokio::time::sleep(Duration::from_secs(5)) => panic!("transfer should complete"),
+            };
+            match msg {
+                Transfer::Progress(step) => steps.push(step),
+                Transfer::Done(result) => break result,
+            }
+        };
+        server.await.expect("server task");
+
+        assert_eq!(result.expect("response").body.len(), SIZE);
+        let size = SIZE as u64;
+        assert!(steps.iter().any(|s| matches!(s, Progress::Sending { .. })));
+        assert!(steps.contains(&Progress::Sending { sent: size, size }));
+        assert_eq!(
+            steps.last(),
+            Some(&Progress::Receiving {
+                received: size,
+                size: Some(size)
+            })
+        );
+
+        drop(state);
+    });
+}
@@ -0,0 +1,42 @@
+use std::time::SystemTime;
+
+use crate::Posix;
+
+/// Where the time manager reads "now" from.
+#[derive(Clone, Copy, Debug)]
+pub enum Clock {
+    /// The system wall clock.
+    Real,
+    /// Starts at `origin` and advances with tokio's clock, so under
+    /// `tokio::time::pause` it only moves with `tokio::time::advance`.
+    Virtual {
+        origin: Posix,
+        started: tokio::time::Instant,
+    },
+}
+
+impl Clock {
+    /// A virtual clock reading `origin` right now.
+    pub fn virtual_at(origin: Posix) -> Self {
+        Clock::Virtual {
+            origin,
+            started: tokio::time::Instant::now(),
+        }
+    }
+
+    pub fn now(&self) -> Posix {
+        self.at(tokio::time::Instant::now())
+    }
+
+    /// The reading at a tokio instant. Interval ticks report their scheduled
+    /// instant so virtual readings don't pick up the timer wheel's rounding.
+    pub(crate) fn at(&self, instant: tokio::time::Instant) -> Posix {
+        match self {
+            Clock::Real => SystemTime::now().into(),
+            Clock::Virtual { origin, started } => {
+                let elapsed = instant.saturating_duration_since(*started).as_millis() as i64;
+                Posix::from_millis(origin.millis() + elapsed)
+            }
+        }
+    }
+}
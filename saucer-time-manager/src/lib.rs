@@ -2,62 +2,126 @@
 //!
 //! Fictional imports expect helpers under `saucer_time_manager::command::*`.
 //! The real helpers live in `requests.rs`; `command.rs` is an empty breadcrumb.
+//!
+//! All readings of "now" go through the state's `Clock`. Tests can swap in
+//! `Clock::virtual_at` so `now`, `notify_at` and `every` advance together
+//! under `tokio::time::advance`. Apps pick the clock through the generated
+//! runtime:
+//!
+//! ```ignore
+//! runtime.init_time_state(move || TimerState::with_clock(Clock::virtual_at(origin)));
+//! ```
 
+mod clock;
+mod posix;
 mod requests;
+mod zone;
+
+pub use clock::Clock;
+pub use posix::Posix;
+pub use zone::{
+    to_day, to_hour, to_millis, to_minute, to_month, to_second, to_weekday, to_year, Month,
+    Weekday, Zone,
+};
 
-pub use requests::{clear, notify_after, notify_at, now, TimeRequest};
+pub use requests::{
+    clear, every, here, notify_after, notify_at, notify_every, now, TimeRequest, TimeSub,
+};
+// Re-exported so generated helpers can name the parameter types used in `requests.rs`.
+pub use std::time::{Duration, SystemTime};
 
-use saucer_core::Router;
+use saucer_core::{EffectManager, Router, SubscriptionManager};
 use std::collections::HashMap;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::sync::{Arc, Mutex};
+
+type Tagger<Msg> = Arc<dyn Fn(Posix) -> Msg + Send + Sync>;
+
+/// A running `every` subscription; taggers are swapped in place so the
+/// interval keeps its phase across re-subscriptions.
+struct EveryTimer<Msg> {
+    taggers: Arc<Mutex<Vec<Tagger<Msg>>>>,
+    handle: tokio::task::JoinHandle<()>,
+}
 
 /// Tracks outstanding timer tasks so they can be cleared deterministically.
-#[derive(Default)]
-pub struct TimerState {
+pub struct TimerState<Msg> {
     tasks: HashMap<u64, tokio::task::JoinHandle<()>>,
+    every: HashMap<Duration, EveryTimer<Msg>>,
+    clock: Clock,
+}
+
+impl<Msg> TimerState<Msg> {
+    /// Empty state reading time from `clock`.
+    pub fn with_clock(clock: Clock) -> Self {
+        Self {
+            tasks: HashMap::new(),
+            every: HashMap::new(),
+            clock,
+        }
+    }
+}
+
+impl<Msg> Default for TimerState<Msg> {
+    fn default() -> Self {
+        Self::with_clock(Clock::Real)
+    }
+}
+
+/// Spawns a task calling `on_tick` every `interval`, starting one interval
+/// from now. Each call gets the tick's scheduled time on `clock`.
+fn spawn_interval(
+    clock: Clock,
+    interval: Duration,
+    mut on_tick: impl FnMut(Posix) + Send + 'static,
+) -> tokio::task::JoinHandle<()> {
+    // tokio intervals reject a zero period. Anchor the first tick to the
+    // moment of scheduling, not to whenever the task is first polled.
+    let period = interval.max(Duration::from_millis(1));
+    let start = tokio::time::Instant::now() + period;
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval_at(start, period);
+        loop {
+            let scheduled = ticker.tick().await;
+            on_tick(clock.at(scheduled));
+        }
+    })
 }
 
 /// Time effect manager using async timers.
 pub struct TimeManager;
 
-impl TimeManager {
-    pub fn init() -> TimerState {
+impl EffectManager for TimeManager {
+    type State<Msg> = TimerState<Msg>;
+    type Request<Msg> = TimeRequest<Msg>;
+    type SelfMsg = ();
+
+    fn init<Msg: Send + 'static>() -> TimerState<Msg> {
         TimerState::default()
     }
 
-    pub fn on_effects<Msg: Send + 'static>(
+    fn on_effects<Msg: Send + 'static>(
         &self,
         router: &Router<Msg, ()>,
-        mut state: TimerState,
+        mut state: TimerState<Msg>,
         effects: Vec<TimeRequest<Msg>>,
-    ) -> TimerState {
+    ) -> TimerState<Msg> {
         for req in effects {
             match req {
                 TimeRequest::Now { returns } => {
-                    let ts = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap_or_else(|_| Duration::from_secs(0))
-                        .as_secs_f64();
-                    router.send_to_app(returns(ts));
+                    router.send_to_app(returns(state.clock.now()));
                 }
-                TimeRequest::NotifyAt {
-                    id,
-                    instant_secs,
-                    returns,
-                } => {
+                TimeRequest::Here { returns } => {
+                    router.send_to_app(returns(Zone::local()));
+                }
+                TimeRequest::NotifyAt { id, at, returns } => {
                     let app_sender = router.app_sender();
                     let returns = returns.clone();
-                    let now = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap_or_else(|_| Duration::from_secs(0))
-                        .as_secs();
-                    let delay = if instant_secs > now {
-                        Duration::from_secs(instant_secs - now)
-                    } else {
-                        Duration::from_secs(0)
-                    };
+                    // Deadlines already in the past fire immediately.
+                    let now: SystemTime = state.clock.now().into();
+                    let delay = at.duration_since(now).unwrap_or(Duration::ZERO);
+                    let deadline = tokio::time::Instant::now() + delay;
                     let handle = tokio::spawn(async move {
-                        tokio::time::sleep(delay).await;
+                        tokio::time::sleep_until(deadline).await;
                         let _ = app_sender.send(returns());
                     });
                     state.tasks.insert(id, handle);
@@ -69,12 +133,27 @@ impl TimeManager {
                 } => {
                     let app_sender = router.app_sender();
                     let returns = returns.clone();
+                    let deadline = tokio::time::Instant::now() + duration;
                     let handle = tokio::spawn(async move {
-                        tokio::time::sleep(duration).await;
+                        tokio::time::sleep_until(deadline).await;
                         let _ = app_sender.send(returns());
                     });
                     state.tasks.insert(id, handle);
                 }
+                TimeRequest::Every {
+                    id,
+                    interval,
+                    returns,
+                } => {
+                    let app_sender = router.app_sender();
+                    let handle = spawn_interval(state.clock, interval, move |now| {
+                        let _ = app_sender.send(returns(now));
+                    });
+                    // Re-arming an id replaces the old interval instead of leaking it.
+                    if let Some(previous) = state.tasks.insert(id, handle) {
+                        previous.abort();
+                    }
+                }
                 TimeRequest::Clear { id, returns } => {
                     if let Some(handle) = state.tasks.remove(&id) {
                         handle.abort();
@@ -86,3 +165,57 @@ impl TimeManager {
         state
     }
 }
+
+impl SubscriptionManager for TimeManager {
+    type Subscription<Msg> = TimeSub<Msg>;
+
+    /// Receives the app's complete set of time subscriptions after every update
+    /// and diffs it against the running intervals: new intervals are started,
+    /// dropped ones are aborted, and kept ones get their taggers replaced.
+    fn on_subscriptions<Msg: Send + 'static>(
+        &self,
+        router: &Router<Msg, ()>,
+        mut state: TimerState<Msg>,
+        subs: Vec<TimeSub<Msg>>,
+    ) -> TimerState<Msg> {
+        let mut wanted: HashMap<Duration, Vec<Tagger<Msg>>> = HashMap::new();
+        for sub in subs {
+            match sub {
+                TimeSub::Every { interval, returns } => {
+                    wanted.entry(interval).or_default().push(returns);
+                }
+            }
+        }
+
+        state.every.retain(|interval, timer| {
+            let keep = wanted.contains_key(interval);
+            if !keep {
+                timer.handle.abort();
+            }
+            keep
+        });
+
+        for (interval, taggers) in wanted {
+            if let Some(timer) = state.every.get(&interval) {
+                *timer.taggers.lock().expect("every taggers mutex poisoned") = taggers;
+                continue;
+            }
+
+            let taggers = Arc::new(Mutex::new(taggers));
+            let task_taggers = Arc::clone(&taggers);
+            let app_sender = router.app_sender();
+            let handle = spawn_interval(state.clock, interval, move |now| {
+                let current = task_taggers
+                    .lock()
+                    .expect("every taggers mutex poisoned")
+                    .clone();
+                for tagger in current {
+                    let _ = app_sender.send(tagger(now));
+                }
+            });
+            state.every.insert(interval, EveryTimer { taggers, handle });
+        }
+
+        state
+    }
+}
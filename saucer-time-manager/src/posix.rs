@@ -0,0 +1,40 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A point in time as milliseconds since the Unix epoch, like Elm's `Time.Posix`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Posix(i64);
+
+impl Posix {
+    pub const fn from_millis(millis: i64) -> Self {
+        Posix(millis)
+    }
+
+    pub fn millis(self) -> i64 {
+        self.0
+    }
+
+    pub fn as_secs_f64(self) -> f64 {
+        self.0 as f64 / 1000.0
+    }
+}
+
+impl From<SystemTime> for Posix {
+    fn from(time: SystemTime) -> Self {
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(after) => Posix(after.as_millis() as i64),
+            // Round down like the positive case: 1.5ms before the epoch is -2.
+            Err(before) => Posix(-(before.duration().as_nanos().div_ceil(1_000_000) as i64)),
+        }
+    }
+}
+
+impl From<Posix> for SystemTime {
+    fn from(posix: Posix) -> Self {
+        let offset = Duration::from_millis(posix.0.unsigned_abs());
+        if posix.0 >= 0 {
+            UNIX_EPOCH + offset
+        } else {
+            UNIX_EPOCH - offset
+        }
+    }
+}
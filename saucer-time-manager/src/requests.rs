@@ -1,16 +1,20 @@
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
+
+use crate::{Posix, Zone};
 
 /// Time effect requests (mirrors vandura-time shape, but with callbacks).
 #[derive(Clone)]
 pub enum TimeRequest<Msg> {
     Now {
-        returns: Arc<dyn Fn(f64) -> Msg + Send + Sync>,
+        returns: Arc<dyn Fn(Posix) -> Msg + Send + Sync>,
+    },
+    Here {
+        returns: Arc<dyn Fn(Zone) -> Msg + Send + Sync>,
     },
     NotifyAt {
         id: u64,
-        // seconds since epoch
-        instant_secs: u64,
+        at: SystemTime,
         returns: Arc<dyn Fn() -> Msg + Send + Sync>,
     },
     NotifyAfter {
@@ -18,6 +22,11 @@ pub enum TimeRequest<Msg> {
         duration: Duration,
         returns: Arc<dyn Fn() -> Msg + Send + Sync>,
     },
+    Every {
+        id: u64,
+        interval: Duration,
+        returns: Arc<dyn Fn(Posix) -> Msg + Send + Sync>,
+    },
     Clear {
         id: u64,
         returns: Arc<dyn Fn() -> Msg + Send + Sync>,
@@ -34,13 +43,12 @@ impl<Msg: 'static> TimeRequest<Msg> {
             TimeRequest::Now { returns } => TimeRequest::Now {
                 returns: Arc::new(move |ts| f(returns(ts))),
             },
-            TimeRequest::NotifyAt {
-                id,
-                instant_secs,
-                returns,
-            } => TimeRequest::NotifyAt {
+            TimeRequest::Here { returns } => TimeRequest::Here {
+                returns: Arc::new(move |zone| f(returns(zone))),
+            },
+            TimeRequest::NotifyAt { id, at, returns } => TimeRequest::NotifyAt {
                 id,
-                instant_secs,
+                at,
                 returns: Arc::new(move || f(returns())),
             },
             TimeRequest::NotifyAfter {
@@ -52,6 +60,15 @@ impl<Msg: 'static> TimeRequest<Msg> {
                 duration,
                 returns: Arc::new(move || f(returns())),
             },
+            TimeRequest::Every {
+                id,
+                interval,
+                returns,
+            } => TimeRequest::Every {
+                id,
+                interval,
+                returns: Arc::new(move |ts| f(returns(ts))),
+            },
             TimeRequest::Clear { id, returns } => TimeRequest::Clear {
                 id,
                 returns: Arc::new(move || f(returns())),
@@ -60,21 +77,53 @@ impl<Msg: 'static> TimeRequest<Msg> {
     }
 }
 
+/// Time subscriptions, returned from an app's `subscriptions(&Model)` function.
+#[derive(Clone)]
+pub enum TimeSub<Msg> {
+    Every {
+        interval: Duration,
+        returns: Arc<dyn Fn(Posix) -> Msg + Send + Sync>,
+    },
+}
+
+impl<Msg: 'static> TimeSub<Msg> {
+    pub fn map<Msg2>(
+        self,
+        f: impl Fn(Msg) -> Msg2 + Send + Sync + Clone + 'static,
+    ) -> TimeSub<Msg2> {
+        let f = Arc::new(f);
+        match self {
+            TimeSub::Every { interval, returns } => TimeSub::Every {
+                interval,
+                returns: Arc::new(move |ts| f(returns(ts))),
+            },
+        }
+    }
+}
+
 /// Fictional helpers used by templates: `use saucer_time_manager::command::<helper>;`
-pub fn now<Msg>(returns: impl Fn(f64) -> Msg + Send + Sync + 'static) -> TimeRequest<Msg> {
+pub fn now<Msg>(returns: impl Fn(Posix) -> Msg + Send + Sync + 'static) -> TimeRequest<Msg> {
     TimeRequest::Now {
         returns: Arc::new(returns),
     }
 }
 
+/// The local time zone, for rendering wall-clock times with `to_hour` and friends.
+pub fn here<Msg>(returns: impl Fn(Zone) -> Msg + Send + Sync + 'static) -> TimeRequest<Msg> {
+    TimeRequest::Here {
+        returns: Arc::new(returns),
+    }
+}
+
+/// Fire once the wall clock reaches `at` (a `SystemTime` or `Posix`).
 pub fn notify_at<Msg>(
     id: u64,
-    instant_secs: u64,
+    at: impl Into<SystemTime>,
     returns: impl Fn() -> Msg + Send + Sync + 'static,
 ) -> TimeRequest<Msg> {
     TimeRequest::NotifyAt {
         id,
-        instant_secs,
+        at: at.into(),
         returns: Arc::new(returns),
     }
 }
@@ -91,6 +140,20 @@ pub fn notify_after<Msg>(
     }
 }
 
+/// Repeat every `interval` until cleared with `clear(id, ..)`. Prefer the
+/// `every` subscription when the timer should follow the model.
+pub fn notify_every<Msg>(
+    id: u64,
+    interval: Duration,
+    returns: impl Fn(Posix) -> Msg + Send + Sync + 'static,
+) -> TimeRequest<Msg> {
+    TimeRequest::Every {
+        id,
+        interval,
+        returns: Arc::new(returns),
+    }
+}
+
 pub fn clear<Msg>(id: u64, returns: impl Fn() -> Msg + Send + Sync + 'static) -> TimeRequest<Msg> {
     TimeRequest::Clear {
         id,
@@ -98,13 +161,99 @@ pub fn clear<Msg>(id: u64, returns: impl Fn() -> Msg + Send + Sync + 'static) ->
     }
 }
 
+/// Subscribe to the current time every `interval`.
+pub fn every<Msg>(
+    interval: Duration,
+    returns: impl Fn(Posix) -> Msg + Send + Sync + 'static,
+) -> TimeSub<Msg> {
+    TimeSub::Every {
+        interval,
+        returns: Arc::new(returns),
+    }
+}
+
 impl<Msg> std::fmt::Debug for TimeRequest<Msg> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             TimeRequest::Now { .. } => f.write_str("TimeRequest::Now"),
+            TimeRequest::Here { .. } => f.write_str("TimeRequest::Here"),
             TimeRequest::NotifyAt { .. } => f.write_str("TimeRequest::NotifyAt"),
             TimeRequest::NotifyAfter { .. } => f.write_str("TimeRequest::NotifyAfter"),
+            TimeRequest::Every { .. } => f.write_str("TimeRequest::Every"),
             TimeRequest::Clear { .. } => f.write_str("TimeRequest::Clear"),
         }
     }
 }
+
+impl<Msg> saucer_core::RequestFields for TimeRequest<Msg> {
+    fn variant(&self) -> &'static str {
+        match self {
+            TimeRequest::Now { .. } => "Now",
+            TimeRequest::Here { .. } => "Here",
+            TimeRequest::NotifyAt { .. } => "NotifyAt",
+            TimeRequest::NotifyAfter { .. } => "NotifyAfter",
+            TimeRequest::Every { .. } => "Every",
+            TimeRequest::Clear { .. } => "Clear",
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        match self {
+            TimeRequest::Now { .. } | TimeRequest::Here { .. } => Vec::new(),
+            TimeRequest::NotifyAt { id, at, .. } => {
+                vec![("id", format!("{:?}", id)), ("at", format!("{:?}", at))]
+            }
+            TimeRequest::NotifyAfter { id, duration, .. } => vec![
+                ("id", format!("{:?}", id)),
+                ("duration", format!("{:?}", duration)),
+            ],
+            TimeRequest::Every { id, interval, .. } => vec![
+                ("id", format!("{:?}", id)),
+                ("interval", format!("{:?}", interval)),
+            ],
+            TimeRequest::Clear { id, .. } => vec![("id", format!("{:?}", id))],
+        }
+    }
+}
+
+impl<Msg> saucer_core::Resolve<Posix, Msg> for TimeRequest<Msg> {
+    fn resolve(&self, result: Posix) -> Option<Msg> {
+        match self {
+            TimeRequest::Now { returns } | TimeRequest::Every { returns, .. } => {
+                Some(returns(result))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<Msg> saucer_core::Resolve<Zone, Msg> for TimeRequest<Msg> {
+    fn resolve(&self, result: Zone) -> Option<Msg> {
+        match self {
+            TimeRequest::Here { returns } => Some(returns(result)),
+            _ => None,
+        }
+    }
+}
+
+impl<Msg> saucer_core::Resolve<(), Msg> for TimeRequest<Msg> {
+    fn resolve(&self, _result: ()) -> Option<Msg> {
+        match self {
+            TimeRequest::NotifyAt { returns, .. }
+            | TimeRequest::NotifyAfter { returns, .. }
+            | TimeRequest::Clear { returns, .. } => Some(returns()),
+            _ => None,
+        }
+    }
+}
+
+impl<Msg> std::fmt::Debug for TimeSub<Msg> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeSub::Every { interval, .. } => f
+                .debug_struct("TimeSub::Every")
+                .field("interval", interval)
+                .finish(),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Pure wall-clock helpers over `(Zone, Posix)`, mirroring Elm's `Time.toYear`,
+//! `Time.toHour`, etc. Only `here` touches the system; these never do.
+
+use crate::Posix;
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// A fixed offset from UTC, like the zone Elm's `Time.here` returns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Zone {
+    offset_minutes: i32,
+}
+
+impl Zone {
+    pub fn utc() -> Self {
+        Zone { offset_minutes: 0 }
+    }
+
+    /// A zone `offset_minutes` east of UTC (negative for west).
+    pub fn fixed(offset_minutes: i32) -> Self {
+        Zone { offset_minutes }
+    }
+
+    pub fn offset_minutes(self) -> i32 {
+        self.offset_minutes
+    }
+
+    /// The system's current UTC offset; used by the manager to answer `here`.
+    pub(crate) fn local() -> Self {
+        let offset_secs = chrono::Local::now().offset().local_minus_utc();
+        Zone::fixed(offset_secs / 60)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Month {
+    Jan,
+    Feb,
+    Mar,
+    Apr,
+    May,
+    Jun,
+    Jul,
+    Aug,
+    Sep,
+    Oct,
+    Nov,
+    Dec,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+fn local_millis(zone: Zone, time: Posix) -> i64 {
+    time.millis() + zone.offset_minutes as i64 * 60_000
+}
+
+/// Civil date for a day count since 1970-01-01 (Howard Hinnant's algorithm).
+fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+    (year, month, day)
+}
+
+fn civil(zone: Zone, time: Posix) -> (i32, u32, u32) {
+    civil_from_days(local_millis(zone, time).div_euclid(MILLIS_PER_DAY))
+}
+
+pub fn to_year(zone: Zone, time: Posix) -> i32 {
+    civil(zone, time).0
+}
+
+pub fn to_month(zone: Zone, time: Posix) -> Month {
+    const MONTHS: [Month; 12] = [
+        Month::Jan,
+        Month::Feb,
+        Month::Mar,
+        Month::Apr,
+        Month::May,
+        Month::Jun,
+        Month::Jul,
+        Month::Aug,
+        Month::Sep,
+        Month::Oct,
+        Month::Nov,
+        Month::Dec,
+    ];
+    MONTHS[civil(zone, time).1 as usize - 1]
+}
+
+/// Day of the month, 1-31.
+pub fn to_day(zone: Zone, time: Posix) -> u32 {
+    civil(zone, time).2
+}
+
+pub fn to_weekday(zone: Zone, time: Posix) -> Weekday {
+    // 1970-01-01 was a Thursday.
+    const WEEKDAYS: [Weekday; 7] = [
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+    ];
+    let days = local_millis(zone, time).div_euclid(MILLIS_PER_DAY);
+    WEEKDAYS[days.rem_euclid(7) as usize]
+}
+
+/// Hour of the day, 0-23.
+pub fn to_hour(zone: Zone, time: Posix) -> u32 {
+    (local_millis(zone, time).rem_euclid(MILLIS_PER_DAY) / 3_600_000) as u32
+}
+
+pub fn to_minute(zone: Zone, time: Posix) -> u32 {
+    (local_millis(zone, time).rem_euclid(3_600_000) / 60_000) as u32
+}
+
+pub fn to_second(zone: Zone, time: Posix) -> u32 {
+    (local_millis(zone, time).rem_euclid(60_000) / 1_000) as u32
+}
+
+/// Milliseconds within the second, 0-999.
+pub fn to_millis(zone: Zone, time: Posix) -> u32 {
+    local_millis(zone, time).rem_euclid(1_000) as u32
+}
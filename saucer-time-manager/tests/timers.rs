@@ -1,7 +1,10 @@
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use saucer_core::RouterChannels;
-use saucer_time_manager::{clear, notify_after, TimeManager};
+use saucer_core::{EffectManager, RouterChannels, SubscriptionManager};
+use saucer_time_manager::{
+    clear, every, here, notify_after, notify_at, notify_every, now, Clock, Posix, TimeManager,
+    TimerState, Zone,
+};
 use tokio::runtime::Builder;
 
 #[test]
@@ -74,3 +77,218 @@ fn clear_cancels_pending_timer() {
         drop(state);
     });
 }
+
+#[test]
+fn every_subscription_ticks_until_removed() {
+    let rt = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        tokio::time::pause();
+        let RouterChannels {
+            router, mut app_rx, ..
+        } = RouterChannels::<&'static str, ()>::new();
+        let manager = TimeManager;
+        let state = TimeManager::init();
+        let state = manager.on_subscriptions(
+            &router,
+            state,
+            vec![every(Duration::from_secs(1), |_| "tick")],
+        );
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(app_rx.recv().await, Some("tick"));
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(app_rx.recv().await, Some("tick"));
+
+        // An empty subscription set stops the interval.
+        let state = manager.on_subscriptions(&router, state, vec![]);
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert!(
+            app_rx.try_recv().is_err(),
+            "interval should have been stopped"
+        );
+
+        drop(state);
+    });
+}
+
+#[test]
+fn notify_every_repeats_until_cleared() {
+    let rt = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        tokio::time::pause();
+        let RouterChannels {
+            router, mut app_rx, ..
+        } = RouterChannels::<&'static str, ()>::new();
+        let manager = TimeManager;
+        let state = TimeManager::init();
+        let state = manager.on_effects(
+            &router,
+            state,
+            vec![notify_every(7, Duration::from_secs(1), |_| "beat")],
+        );
+
+        for _ in 0..3 {
+            tokio::time::advance(Duration::from_secs(1)).await;
+            assert_eq!(app_rx.recv().await, Some("beat"));
+        }
+
+        let state = manager.on_effects(&router, state, vec![clear(7, || "cleared")]);
+        assert_eq!(app_rx.recv().await, Some("cleared"));
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert!(app_rx.try_recv().is_err(), "cleared interval should stop");
+
+        drop(state);
+    });
+}
+
+#[test]
+fn notify_at_schedules_with_millisecond_precision() {
+    let rt = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        tokio::time::pause();
+        let RouterChannels {
+            router, mut app_rx, ..
+        } = RouterChannels::<&'static str, ()>::new();
+        let manager = TimeManager;
+        let state = TimeManager::init();
+        let deadline = SystemTime::now() + Duration::from_millis(1500);
+        let state = manager.on_effects(&router, state, vec![notify_at(3, deadline, || "due")]);
+
+        // Whole-second rounding would fire at 1s (or 2s); it must be neither.
+        tokio::time::advance(Duration::from_millis(1400)).await;
+        assert!(app_rx.try_recv().is_err(), "fired before the deadline");
+        tokio::time::advance(Duration::from_millis(200)).await;
+        assert_eq!(app_rx.recv().await, Some("due"));
+
+        drop(state);
+    });
+}
+
+#[test]
+fn now_returns_posix_millis() {
+    let rt = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        let RouterChannels {
+            router, mut app_rx, ..
+        } = RouterChannels::<Posix, ()>::new();
+        let manager = TimeManager;
+        let before = Posix::from(SystemTime::now());
+        let state = manager.on_effects(&router, TimeManager::init(), vec![now(|t| t)]);
+        let after = Posix::from(SystemTime::now());
+
+        let reported = app_rx.recv().await.expect("now should answer");
+        assert!(before <= reported && reported <= after);
+
+        drop(state);
+    });
+}
+
+#[test]
+fn posix_round_trips_through_system_time() {
+    let posix = Posix::from_millis(1_700_000_000_123);
+    let time: SystemTime = posix.into();
+    assert_eq!(
+        time.duration_since(UNIX_EPOCH).unwrap(),
+        Duration::from_millis(1_700_000_000_123)
+    );
+    assert_eq!(Posix::from(time), posix);
+    assert_eq!(posix.as_secs_f64(), 1_700_000_000.123);
+}
+
+#[test]
+fn posix_floors_times_before_the_epoch() {
+    let time = UNIX_EPOCH - Duration::from_micros(1_500);
+    assert_eq!(Posix::from(time), Posix::from_millis(-2));
+    let time = UNIX_EPOCH - Duration::from_millis(3);
+    assert_eq!(Posix::from(time), Posix::from_millis(-3));
+    let time = UNIX_EPOCH + Duration::from_micros(1_500);
+    assert_eq!(Posix::from(time), Posix::from_millis(1));
+}
+
+#[test]
+fn here_reports_the_local_offset() {
+    let rt = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        let RouterChannels {
+            router, mut app_rx, ..
+        } = RouterChannels::<Zone, ()>::new();
+        let manager = TimeManager;
+        let state = manager.on_effects(&router, TimeManager::init(), vec![here(|z| z)]);
+
+        let zone = app_rx.recv().await.expect("here should answer");
+        // Real-world offsets span UTC-12:00 to UTC+14:00.
+        assert!((-12 * 60..=14 * 60).contains(&zone.offset_minutes()));
+
+        drop(state);
+    });
+}
+
+#[test]
+fn virtual_clock_advances_now_notify_at_and_every_together() {
+    let rt = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        tokio::time::pause();
+        let RouterChannels {
+            router, mut app_rx, ..
+        } = RouterChannels::<String, ()>::new();
+        let manager = TimeManager;
+        let origin = Posix::from_millis(1_700_000_000_000);
+        let state = TimerState::with_clock(Clock::virtual_at(origin));
+        let stamp = |t: Posix| format!("now {}", t.millis());
+        let state = manager.on_effects(&router, state, vec![now(stamp)]);
+        let state = manager.on_subscriptions(
+            &router,
+            state,
+            vec![every(Duration::from_secs(1), |t| {
+                format!("tick {}", t.millis())
+            })],
+        );
+        assert_eq!(app_rx.recv().await.unwrap(), "now 1700000000000");
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        let state = manager.on_effects(&router, state, vec![now(stamp)]);
+        assert_eq!(app_rx.recv().await.unwrap(), "now 1700000001000");
+        assert_eq!(app_rx.recv().await.unwrap(), "tick 1700000001000");
+
+        // The deadline is on the virtual timeline, half a second past the
+        // second tick.
+        let deadline = Posix::from_millis(origin.millis() + 2_500);
+        let state = manager.on_effects(
+            &router,
+            state,
+            vec![notify_at(1, deadline, || "deadline".to_string())],
+        );
+        tokio::time::advance(Duration::from_millis(999)).await;
+        assert_eq!(app_rx.recv().await.unwrap(), "tick 1700000002000");
+        tokio::time::advance(Duration::from_millis(400)).await;
+        assert!(app_rx.try_recv().is_err(), "fired before the deadline");
+        tokio::time::advance(Duration::from_millis(200)).await;
+        assert_eq!(app_rx.recv().await.unwrap(), "deadline");
+
+        drop(state);
+    });
+}
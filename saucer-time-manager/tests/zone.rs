@@ -0,0 +1,49 @@
+use saucer_time_manager::{
+    to_day, to_hour, to_millis, to_minute, to_month, to_second, to_weekday, to_year, Month, Posix,
+    Weekday, Zone,
+};
+
+// 2024-02-29T23:59:58.250Z, a Thursday.
+const LEAP_DAY: Posix = Posix::from_millis(1_709_251_198_250);
+
+#[test]
+fn utc_parts() {
+    let utc = Zone::utc();
+    assert_eq!(to_year(utc, LEAP_DAY), 2024);
+    assert_eq!(to_month(utc, LEAP_DAY), Month::Feb);
+    assert_eq!(to_day(utc, LEAP_DAY), 29);
+    assert_eq!(to_weekday(utc, LEAP_DAY), Weekday::Thu);
+    assert_eq!(to_hour(utc, LEAP_DAY), 23);
+    assert_eq!(to_minute(utc, LEAP_DAY), 59);
+    assert_eq!(to_second(utc, LEAP_DAY), 58);
+    assert_eq!(to_millis(utc, LEAP_DAY), 250);
+}
+
+#[test]
+fn offsets_cross_day_and_month_boundaries() {
+    // Two hours east of UTC it is already March 1st, a Friday.
+    let east = Zone::fixed(120);
+    assert_eq!(to_month(east, LEAP_DAY), Month::Mar);
+    assert_eq!(to_day(east, LEAP_DAY), 1);
+    assert_eq!(to_weekday(east, LEAP_DAY), Weekday::Fri);
+    assert_eq!(to_hour(east, LEAP_DAY), 1);
+
+    // Five and a half hours west keeps the half-hour offset in the minutes.
+    let west = Zone::fixed(-330);
+    assert_eq!(to_day(west, LEAP_DAY), 29);
+    assert_eq!(to_hour(west, LEAP_DAY), 18);
+    assert_eq!(to_minute(west, LEAP_DAY), 29);
+}
+
+#[test]
+fn handles_times_before_the_epoch() {
+    // 1969-12-31T23:59:59.999Z, a Wednesday.
+    let before = Posix::from_millis(-1);
+    let utc = Zone::utc();
+    assert_eq!(to_year(utc, before), 1969);
+    assert_eq!(to_month(utc, before), Month::Dec);
+    assert_eq!(to_day(utc, before), 31);
+    assert_eq!(to_weekday(utc, before), Weekday::Wed);
+    assert_eq!(to_hour(utc, before), 23);
+    assert_eq!(to_millis(utc, before), 999);
+}